@@ -1,111 +1,64 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use gobit::{Color, Goban};
+use gobit::{Goban, record::GameRecord, sgf};
 
-const LIAN_XIAO_KANG_DONGYUN: [(char, (u8, u8)); 166] = [
-    ('B', (16, 3)), ('W', (3, 3)), ('B', (15, 16)), ('W', (3, 15)), ('B', (13, 3)), ('W', (15, 14)), ('B', (16, 14)),
-    ('W', (16, 13)), ('B', (16, 15)), ('W', (15, 13)), ('B', (13, 16)), ('W', (15, 9)), ('B', (2, 16)), ('W', (2, 15)),
-    ('B', (3, 16)), ('W', (4, 15)), ('B', (4, 16)), ('W', (5, 15)), ('B', (5, 16)), ('W', (6, 16)), ('B', (5, 2)),
-    ('W', (2, 5)), ('B', (6, 15)), ('W', (7, 16)), ('B', (1, 16)), ('W', (6, 14)), ('B', (16, 7)), ('W', (14, 7)),
-    ('B', (12, 9)), ('W', (9, 11)), ('B', (14, 8)), ('W', (15, 8)), ('B', (15, 7)), ('W', (13, 7)), ('B', (14, 9)),
-    ('W', (15, 10)), ('B', (14, 10)), ('W', (16, 5)), ('B', (17, 5)), ('W', (11, 7)), ('B', (10, 9)), ('W', (11, 2)),
-    ('B', (12, 4)), ('W', (14, 2)), ('B', (13, 2)), ('W', (15, 4)), ('B', (16, 4)), ('W', (15, 5)), ('B', (17, 6)),
-    ('W', (15, 3)), ('B', (16, 2)), ('W', (13, 1)), ('B', (12, 1)), ('W', (15, 1)), ('B', (13, 0)), ('W', (14, 1)),
-    ('B', (12, 2)), ('W', (13, 5)), ('B', (17, 9)), ('W', (10, 4)), ('B', (9, 2)), ('W', (12, 14)), ('B', (15, 11)),
-    ('W', (16, 11)), ('B', (14, 11)), ('W', (16, 10)), ('B', (10, 12)), ('W', (14, 15)), ('B', (14, 16)),
-    ('W', (10, 14)), ('B', (9, 12)), ('W', (10, 2)), ('B', (9, 3)), ('W', (11, 4)), ('B', (10, 1)), ('W', (9, 8)),
-    ('B', (12, 5)), ('W', (12, 6)), ('B', (10, 6)), ('W', (9, 9)), ('B', (10, 11)), ('W', (8, 11)), ('B', (13, 13)),
-    ('W', (12, 13)), ('B', (12, 12)), ('W', (13, 12)), ('B', (14, 13)), ('W', (11, 16)), ('B', (11, 5)), ('W', (10, 8)),
-    ('B', (9, 14)), ('W', (9, 15)), ('B', (10, 15)), ('W', (11, 15)), ('B', (8, 15)), ('W', (10, 16)), ('B', (8, 12)),
-    ('W', (7, 12)), ('B', (7, 13)), ('W', (6, 13)), ('B', (8, 6)), ('W', (11, 1)), ('B', (9, 4)), ('W', (1, 15)),
-    ('B', (10, 10)), ('W', (3, 8)), ('B', (5, 9)), ('W', (4, 2)), ('B', (3, 9)), ('W', (4, 9)), ('B', (4, 10)),
-    ('W', (4, 8)), ('B', (3, 10)), ('W', (5, 10)), ('B', (2, 8)), ('W', (2, 7)), ('B', (1, 7)), ('W', (1, 8)),
-    ('B', (2, 9)), ('W', (1, 9)), ('B', (3, 7)), ('W', (2, 6)), ('B', (1, 10)), ('W', (1, 6)), ('B', (5, 11)),
-    ('W', (6, 10)), ('B', (6, 11)), ('W', (5, 8)), ('B', (2, 11)), ('W', (7, 11)), ('B', (4, 12)), ('W', (9, 1)),
-    ('B', (8, 1)), ('W', (10, 0)), ('B', (12, 0)), ('W', (7, 1)), ('B', (7, 2)), ('W', (8, 0)), ('B', (8, 2)),
-    ('W', (5, 1)), ('B', (6, 1)), ('W', (6, 0)), ('B', (7, 0)), ('W', (1, 12)), ('B', (4, 1)), ('W', (3, 12)),
-    ('B', (8, 16)), ('W', (7, 15)), ('B', (8, 14)), ('W', (17, 10)), ('B', (2, 12)), ('W', (3, 13)), ('B', (17, 12)),
-    ('W', (17, 13)), ('B', (3, 2)), ('W', (4, 3)), ('B', (2, 2)), ('W', (15, 15)), ('B', (2, 3)), ('W', (5, 3)),
-    ('B', (1, 13)), ('W', (3, 11)), ('B', (4, 11)), ('W', (6, 9)), ('B', (0, 14)), ('W', (0, 15))
-];
+const LIAN_XIAO_KANG_DONGYUN: &str = "\
+    (;GM[1]FF[4]SZ[19]\
+    ;B[qd];W[dd];B[pq];W[dp];B[nd];W[po];B[qo];W[qn];B[qp];W[pn];B[nq];W[pj];B[cq];W[cp];B[dq];W[ep]\
+    ;B[eq];W[fp];B[fq];W[gq];B[fc];W[cf];B[gp];W[hq];B[bq];W[go];B[qh];W[oh];B[mj];W[jl];B[oi];W[pi]\
+    ;B[ph];W[nh];B[oj];W[pk];B[ok];W[qf];B[rf];W[lh];B[kj];W[lc];B[me];W[oc];B[nc];W[pe];B[qe];W[pf]\
+    ;B[rg];W[pd];B[qc];W[nb];B[mb];W[pb];B[na];W[ob];B[mc];W[nf];B[rj];W[ke];B[jc];W[mo];B[pl];W[ql]\
+    ;B[ol];W[qk];B[km];W[op];B[oq];W[ko];B[jm];W[kc];B[jd];W[le];B[kb];W[ji];B[mf];W[mg];B[kg];W[jj]\
+    ;B[kl];W[il];B[nn];W[mn];B[mm];W[nm];B[on];W[lq];B[lf];W[ki];B[jo];W[jp];B[kp];W[lp];B[ip];W[kq]\
+    ;B[im];W[hm];B[hn];W[gn];B[ig];W[lb];B[je];W[bp];B[kk];W[di];B[fj];W[ec];B[dj];W[ej];B[ek];W[ei]\
+    ;B[dk];W[fk];B[ci];W[ch];B[bh];W[bi];B[cj];W[bj];B[dh];W[cg];B[bk];W[bg];B[fl];W[gk];B[gl];W[fi]\
+    ;B[cl];W[hl];B[em];W[jb];B[ib];W[ka];B[ma];W[hb];B[hc];W[ia];B[ic];W[fb];B[gb];W[ga];B[ha];W[bm]\
+    ;B[eb];W[dm];B[iq];W[hp];B[io];W[rk];B[cm];W[dn];B[rm];W[rn];B[dc];W[ed];B[cc];W[pp];B[cd];W[fd]\
+    ;B[bn];W[dl];B[el];W[gj];B[ao];W[ap])";
 
-const LI_XUANHAO_SHIN_JINSEO: [(char, (u8, u8)); 170] = [
-    ('B', (15, 3)), ('W', (3, 15)), ('B', (15, 16)), ('W', (3, 2)), ('B', (2, 16)), ('W', (2, 15)), ('B', (3, 16)),
-    ('W', (5, 16)), ('B', (5, 17)), ('W', (6, 17)), ('B', (4, 16)), ('W', (5, 15)), ('B', (4, 15)), ('W', (4, 14)),
-    ('B', (3, 14)), ('W', (1, 14)), ('B', (3, 13)), ('W', (4, 13)), ('B', (4, 12)), ('W', (5, 12)), ('B', (2, 12)),
-    ('W', (4, 11)), ('B', (3, 12)), ('W', (1, 16)), ('B', (4, 17)), ('W', (2, 10)), ('B', (5, 11)), ('W', (5, 13)),
-    ('B', (6, 16)), ('W', (4, 10)), ('B', (3, 3)), ('W', (4, 2)), ('B', (2, 3)), ('W', (1, 11)), ('B', (2, 2)),
-    ('W', (13, 2)), ('B', (6, 15)), ('W', (6, 11)), ('B', (7, 2)), ('W', (4, 3)), ('B', (10, 3)), ('W', (4, 5)),
-    ('B', (2, 6)), ('W', (9, 5)), ('B', (7, 6)), ('W', (8, 4)), ('B', (5, 10)), ('W', (7, 10)), ('B', (5, 9)),
-    ('W', (4, 9)), ('B', (5, 8)), ('W', (4, 8)), ('B', (7, 13)), ('W', (7, 12)), ('B', (4, 7)), ('W', (2, 5)),
-    ('B', (7, 4)), ('W', (7, 3)), ('B', (6, 3)), ('W', (8, 3)), ('B', (1, 5)), ('W', (6, 2)), ('B', (8, 12)),
-    ('W', (5, 7)), ('B', (6, 1)), ('W', (5, 2)), ('B', (8, 2)), ('W', (9, 2)), ('B', (9, 6)), ('W', (9, 1)),
-    ('B', (6, 7)), ('W', (5, 6)), ('B', (7, 8)), ('W', (9, 10)), ('B', (8, 5)), ('W', (10, 5)), ('B', (8, 11)),
-    ('W', (7, 9)), ('B', (10, 6)), ('W', (11, 5)), ('B', (11, 6)), ('W', (12, 5)), ('B', (12, 6)), ('W', (13, 5)),
-    ('B', (8, 10)), ('W', (8, 9)), ('B', (9, 9)), ('W', (8, 8)), ('B', (8, 7)), ('W', (9, 8)), ('B', (10, 9)),
-    ('W', (10, 8)), ('B', (11, 8)), ('W', (11, 9)), ('B', (10, 10)), ('W', (12, 8)), ('B', (11, 7)), ('W', (1, 12)),
-    ('B', (5, 14)), ('W', (6, 12)), ('B', (11, 10)), ('W', (12, 9)), ('B', (12, 10)), ('W', (15, 14)), ('B', (13, 15)),
-    ('W', (13, 14)), ('B', (16, 15)), ('W', (14, 11)), ('B', (14, 9)), ('W', (12, 15)), ('B', (14, 15)),
-    ('W', (12, 13)), ('B', (16, 12)), ('W', (13, 10)), ('B', (13, 9)), ('W', (12, 11)), ('B', (13, 8)), ('W', (12, 16)),
-    ('B', (14, 2)), ('W', (13, 1)), ('B', (13, 3)), ('W', (12, 3)), ('B', (11, 14)), ('W', (12, 14)), ('B', (10, 16)),
-    ('W', (10, 15)), ('B', (9, 16)), ('W', (16, 14)), ('B', (14, 14)), ('W', (14, 13)), ('B', (15, 13)),
-    ('W', (15, 12)), ('B', (16, 13)), ('W', (16, 11)), ('B', (17, 14)), ('W', (17, 10)), ('B', (13, 12)),
-    ('W', (15, 11)), ('B', (6, 13)), ('W', (6, 10)), ('B', (6, 8)), ('W', (2, 14)), ('B', (11, 13)), ('W', (10, 12)),
-    ('B', (10, 13)), ('W', (9, 13)), ('B', (9, 14)), ('W', (17, 7)), ('B', (17, 6)), ('W', (16, 7)), ('B', (14, 1)),
-    ('W', (17, 3)), ('B', (16, 4)), ('W', (17, 4)), ('B', (17, 5)), ('W', (16, 1)), ('B', (15, 6)), ('W', (15, 1)),
-    ('B', (16, 9)), ('W', (17, 9)), ('B', (16, 8)), ('W', (17, 8)), ('B', (13, 0)), ('W', (14, 6)), ('B', (15, 7)),
-    ('W', (16, 6)), ('B', (16, 5)), ('W', (15, 5)), ('B', (12, 4)), ('W', (13, 4))
-];
+const LI_XUANHAO_SHIN_JINSEO: &str = "\
+    (;GM[1]FF[4]SZ[19]\
+    ;B[pd];W[dp];B[pq];W[dc];B[cq];W[cp];B[dq];W[fq];B[fr];W[gr];B[eq];W[fp];B[ep];W[eo];B[do];W[bo]\
+    ;B[dn];W[en];B[em];W[fm];B[cm];W[el];B[dm];W[bq];B[er];W[ck];B[fl];W[fn];B[gq];W[ek];B[dd];W[ec]\
+    ;B[cd];W[bl];B[cc];W[nc];B[gp];W[gl];B[hc];W[ed];B[kd];W[ef];B[cg];W[jf];B[hg];W[ie];B[fk];W[hk]\
+    ;B[fj];W[ej];B[fi];W[ei];B[hn];W[hm];B[eh];W[cf];B[he];W[hd];B[gd];W[id];B[bf];W[gc];B[im];W[fh]\
+    ;B[gb];W[fc];B[ic];W[jc];B[jg];W[jb];B[gh];W[fg];B[hi];W[jk];B[if];W[kf];B[il];W[hj];B[kg];W[lf]\
+    ;B[lg];W[mf];B[mg];W[nf];B[ik];W[ij];B[jj];W[ii];B[ih];W[ji];B[kj];W[ki];B[li];W[lj];B[kk];W[mi]\
+    ;B[lh];W[bm];B[fo];W[gm];B[lk];W[mj];B[mk];W[po];B[np];W[no];B[qp];W[ol];B[oj];W[mp];B[op];W[mn]\
+    ;B[qm];W[nk];B[nj];W[ml];B[ni];W[mq];B[oc];W[nb];B[nd];W[md];B[lo];W[mo];B[kq];W[kp];B[jq];W[qo]\
+    ;B[oo];W[on];B[pn];W[pm];B[qn];W[ql];B[ro];W[rk];B[nm];W[pl];B[gn];W[gk];B[gi];W[co];B[ln];W[km]\
+    ;B[kn];W[jn];B[jo];W[rh];B[rg];W[qh];B[ob];W[rd];B[qe];W[re];B[rf];W[qb];B[pg];W[pb];B[qj];W[rj]\
+    ;B[qi];W[ri];B[na];W[og];B[ph];W[qg];B[qf];W[pf];B[me];W[ne])";
 
-const YANG_DINGXIN_SHIBANO_TORAMARU: [(char, (u8, u8)); 172] = [
-    ('B', (15, 3)), ('W', (3, 15)), ('B', (15, 15)), ('W', (3, 2)), ('B', (2, 4)), ('W', (4, 3)), ('B', (2, 16)),
-    ('W', (2, 15)), ('B', (3, 16)), ('W', (4, 15)), ('B', (5, 16)), ('W', (6, 14)), ('B', (6, 17)), ('W', (2, 7)),
-    ('B', (13, 2)), ('W', (4, 7)), ('B', (16, 8)), ('W', (12, 3)), ('B', (13, 3)), ('W', (12, 5)), ('B', (13, 16)),
-    ('W', (10, 2)), ('B', (1, 2)), ('W', (1, 6)), ('B', (7, 13)), ('W', (7, 14)), ('B', (9, 13)), ('W', (6, 11)),
-    ('B', (6, 2)), ('W', (5, 2)), ('B', (3, 6)), ('W', (3, 7)), ('B', (5, 4)), ('W', (4, 4)), ('B', (5, 1)),
-    ('W', (7, 2)), ('B', (4, 1)), ('W', (6, 3)), ('B', (3, 1)), ('W', (6, 1)), ('B', (4, 2)), ('W', (17, 14)),
-    ('B', (17, 15)), ('W', (16, 6)), ('B', (5, 3)), ('W', (17, 3)), ('B', (6, 2)), ('W', (16, 2)), ('B', (14, 6)),
-    ('W', (5, 2)), ('B', (6, 4)), ('W', (16, 14)), ('B', (16, 15)), ('W', (16, 10)), ('B', (17, 5)), ('W', (16, 4)),
-    ('B', (11, 3)), ('W', (14, 5)), ('B', (15, 5)), ('W', (16, 5)), ('B', (11, 2)), ('W', (15, 4)), ('B', (7, 4)),
-    ('W', (9, 4)), ('B', (13, 5)), ('W', (15, 6)), ('B', (13, 4)), ('W', (14, 7)), ('B', (13, 6)), ('W', (10, 3)),
-    ('B', (11, 1)), ('W', (9, 6)), ('B', (6, 7)), ('W', (6, 9)), ('B', (14, 8)), ('W', (13, 7)), ('B', (12, 6)),
-    ('W', (9, 8)), ('B', (15, 7)), ('W', (4, 6)), ('B', (6, 2)), ('W', (1, 16)), ('B', (1, 17)), ('W', (5, 2)),
-    ('B', (8, 3)), ('W', (10, 1)), ('B', (6, 2)), ('W', (13, 8)), ('B', (14, 9)), ('W', (5, 2)), ('B', (10, 5)),
-    ('W', (9, 5)), ('B', (6, 2)), ('W', (16, 7)), ('B', (15, 8)), ('W', (5, 2)), ('B', (7, 3)), ('W', (11, 8)),
-    ('B', (14, 11)), ('W', (13, 10)), ('B', (14, 10)), ('W', (15, 12)), ('B', (17, 9)), ('W', (11, 7)), ('B', (12, 12)),
-    ('W', (6, 2)), ('B', (3, 3)), ('W', (4, 5)), ('B', (8, 14)), ('W', (17, 10)), ('B', (15, 11)), ('W', (16, 12)),
-    ('B', (15, 13)), ('W', (14, 12)), ('B', (13, 11)), ('W', (13, 12)), ('B', (12, 11)), ('W', (15, 14)),
-    ('B', (14, 13)), ('W', (14, 15)), ('B', (14, 16)), ('W', (13, 14)), ('B', (17, 12)), ('W', (17, 11)),
-    ('B', (13, 13)), ('W', (18, 12)), ('B', (16, 13)), ('W', (17, 13)), ('B', (16, 11)), ('W', (17, 12)),
-    ('B', (12, 13)), ('W', (18, 10)), ('B', (6, 13)), ('W', (5, 13)), ('B', (5, 12)), ('W', (6, 12)), ('B', (5, 14)),
-    ('W', (4, 13)), ('B', (5, 15)), ('W', (1, 15)), ('B', (17, 7)), ('W', (18, 14)), ('B', (8, 1)), ('W', (8, 2)),
-    ('B', (9, 3)), ('W', (10, 4)), ('B', (15, 1)), ('W', (15, 2)), ('B', (14, 1)), ('W', (16, 1)), ('B', (9, 2)),
-    ('W', (9, 1)), ('B', (6, 0)), ('W', (12, 4)), ('B', (17, 6)), ('W', (14, 4)), ('B', (2, 12)), ('W', (3, 11)),
-    ('B', (5, 8)), ('W', (8, 12)), ('B', (8, 13)), ('W', (6, 5)), ('B', (2, 11)), ('W', (3, 10)), ('B', (2, 10)),
-    ('W', (3, 13)), ('B', (3, 9)), ('W', (4, 9)), ('B', (4, 10)), ('W', (4, 11)), ('B', (4, 8)), ('W', (7, 5))
-];
+const YANG_DINGXIN_SHIBANO_TORAMARU: &str = "\
+    (;GM[1]FF[4]SZ[19]\
+    ;B[pd];W[dp];B[pp];W[dc];B[ce];W[ed];B[cq];W[cp];B[dq];W[ep];B[fq];W[go];B[gr];W[ch];B[nc];W[eh]\
+    ;B[qi];W[md];B[nd];W[mf];B[nq];W[kc];B[bc];W[bg];B[hn];W[ho];B[jn];W[gl];B[gc];W[fc];B[dg];W[dh]\
+    ;B[fe];W[ee];B[fb];W[hc];B[eb];W[gd];B[db];W[gb];B[ec];W[ro];B[rp];W[qg];B[fd];W[rd];B[gc];W[qc]\
+    ;B[og];W[fc];B[ge];W[qo];B[qp];W[qk];B[rf];W[qe];B[ld];W[of];B[pf];W[qf];B[lc];W[pe];B[he];W[je]\
+    ;B[nf];W[pg];B[ne];W[oh];B[ng];W[kd];B[lb];W[jg];B[gh];W[gj];B[oi];W[nh];B[mg];W[ji];B[ph];W[eg]\
+    ;B[gc];W[bq];B[br];W[fc];B[id];W[kb];B[gc];W[ni];B[oj];W[fc];B[kf];W[jf];B[gc];W[qh];B[pi];W[fc]\
+    ;B[hd];W[li];B[ol];W[nk];B[ok];W[pm];B[rj];W[lh];B[mm];W[gc];B[dd];W[ef];B[io];W[rk];B[pl];W[qm]\
+    ;B[pn];W[om];B[nl];W[nm];B[ml];W[po];B[on];W[op];B[oq];W[no];B[rm];W[rl];B[nn];W[sm];B[qn];W[rn]\
+    ;B[ql];W[rm];B[mn];W[sk];B[gn];W[fn];B[fm];W[gm];B[fo];W[en];B[fp];W[bp];B[rh];W[so];B[ib];W[ic]\
+    ;B[jd];W[ke];B[pb];W[pc];B[ob];W[qb];B[jc];W[jb];B[ga];W[me];B[rg];W[oe];B[cm];W[dl];B[fi];W[im]\
+    ;B[in];W[gf];B[cl];W[dk];B[ck];W[dn];B[dj];W[ej];B[ek];W[el];B[ei];W[hf])";
 
-fn playout(moves: &[(char, (u8, u8))]) -> Goban {
-    let mut goban = Goban::new(19, 19);
-
-    for (color, (x, y)) in moves {
-        let at = (*x, *y).into();
-        let color = match color {
-            'B' => Color::Black,
-            'W' => Color::White,
-            _ => unreachable!(),
-        };
-
-        debug_assert!(goban.is_legal(at, color));
-        goban.play(at, color);
-    }
+fn record(sgf: &str) -> GameRecord {
+    GameRecord::from_sgf(&sgf::parse(sgf).unwrap()[0]).unwrap()
+}
 
-    goban
+fn playout(record: &GameRecord) -> Goban {
+    record.replay().play_to_end().unwrap()
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("lian_xiao_kang_dongyun", |b| b.iter(|| playout(black_box(&LIAN_XIAO_KANG_DONGYUN))));
-    c.bench_function("li_xuanhao_shin_jinseo", |b| b.iter(|| playout(black_box(&LI_XUANHAO_SHIN_JINSEO))));
-    c.bench_function("yang_dingxin_shibano_toramaru", |b| b.iter(|| playout(black_box(&YANG_DINGXIN_SHIBANO_TORAMARU))));
+    let lian_xiao_kang_dongyun = record(LIAN_XIAO_KANG_DONGYUN);
+    let li_xuanhao_shin_jinseo = record(LI_XUANHAO_SHIN_JINSEO);
+    let yang_dingxin_shibano_toramaru = record(YANG_DINGXIN_SHIBANO_TORAMARU);
+
+    c.bench_function("lian_xiao_kang_dongyun", |b| b.iter(|| playout(black_box(&lian_xiao_kang_dongyun))));
+    c.bench_function("li_xuanhao_shin_jinseo", |b| b.iter(|| playout(black_box(&li_xuanhao_shin_jinseo))));
+    c.bench_function("yang_dingxin_shibano_toramaru", |b| b.iter(|| playout(black_box(&yang_dingxin_shibano_toramaru))));
 }
 
 criterion_group!(benches, criterion_benchmark);
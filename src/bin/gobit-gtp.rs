@@ -0,0 +1,6 @@
+use gobit::gtp::Engine;
+use std::io::{self, BufReader};
+
+fn main() -> io::Result<()> {
+    Engine::new().run(BufReader::new(io::stdin()), io::stdout().lock())
+}
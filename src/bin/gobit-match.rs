@@ -0,0 +1,141 @@
+use gobit::{
+    Color,
+    gtp::{play_game, Client, ClientError, MatchStats, Sprt, SprtResult},
+};
+use std::{
+    env, fs,
+    io::BufReader,
+    path::PathBuf,
+    process::{self, ChildStdin, ChildStdout, Command},
+};
+
+const USAGE: &str = "usage: gobit-match --first <command> --second <command> [--games <n>] [--size <n>] [--komi <komi>] [--max-moves <n>] [--sgf <directory>] [--sprt <elo0> <elo1> [--alpha <p>] [--beta <p>]]";
+
+struct Options {
+    first: String,
+    second: String,
+    games: usize,
+    size: usize,
+    komi: f32,
+    max_moves: usize,
+    sgf: Option<PathBuf>,
+    sprt: Option<(f32, f32)>,
+    alpha: f32,
+    beta: f32,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        first: String::new(),
+        second: String::new(),
+        games: 100,
+        size: 19,
+        komi: 7.5,
+        max_moves: 1000,
+        sgf: None,
+        sprt: None,
+        alpha: 0.05,
+        beta: 0.05,
+    };
+    let mut args = env::args().skip(1);
+
+    fn next_value<T: std::str::FromStr>(args: &mut impl Iterator<Item=String>, name: &str) -> Result<T, String> {
+        args.next()
+            .and_then(|arg| arg.parse::<T>().ok())
+            .ok_or_else(|| format!("invalid value for {}", name))
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--first" => { options.first = next_value(&mut args, &arg)? },
+            "--second" => { options.second = next_value(&mut args, &arg)? },
+            "--games" => { options.games = next_value(&mut args, &arg)? },
+            "--size" => { options.size = next_value(&mut args, &arg)? },
+            "--komi" => { options.komi = next_value(&mut args, &arg)? },
+            "--max-moves" => { options.max_moves = next_value(&mut args, &arg)? },
+            "--sgf" => { options.sgf = Some(next_value(&mut args, &arg)?) },
+            "--sprt" => { options.sprt = Some((next_value(&mut args, &arg)?, next_value(&mut args, &arg)?)) },
+            "--alpha" => { options.alpha = next_value(&mut args, &arg)? },
+            "--beta" => { options.beta = next_value(&mut args, &arg)? },
+            _ => { return Err(format!("unknown argument {}", arg)) }
+        }
+    }
+
+    if options.first.trim().is_empty() || options.second.trim().is_empty() {
+        return Err("both --first and --second are required".into());
+    }
+
+    Ok(options)
+}
+
+fn spawn(command_line: &str) -> Result<Client<BufReader<ChildStdout>, ChildStdin>, ClientError> {
+    let mut words = command_line.split_whitespace();
+    let mut command = Command::new(words.next().unwrap_or(""));
+    command.args(words);
+
+    Client::spawn(command)
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|message| {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    });
+
+    if let Err(err) = run(&options) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = spawn(&options.first)?;
+    let mut second = spawn(&options.second)?;
+    let sprt = options.sprt.map(|(elo0, elo1)| Sprt::new(elo0, elo1, options.alpha, options.beta));
+    let mut stats = MatchStats::default();
+
+    if let Some(directory) = &options.sgf {
+        fs::create_dir_all(directory)?;
+    }
+
+    for game in 0..options.games {
+        let first_color = if game % 2 == 0 { Color::Black } else { Color::White };
+        let outcome = match first_color {
+            Color::Black => play_game(&mut first, &mut second, options.size, options.komi, options.max_moves)?,
+            Color::White => play_game(&mut second, &mut first, options.size, options.komi, options.max_moves)?,
+        };
+        let record = outcome.record();
+
+        if !outcome.is_void() {
+            stats.push(outcome.winner().map(|winner| winner == first_color));
+        }
+        println!(
+            "game {}: {} (B) vs {} (W), {}",
+            game + 1,
+            record.black_name().unwrap_or("?"),
+            record.white_name().unwrap_or("?"),
+            record.result().unwrap_or("?")
+        );
+
+        if let Some(directory) = &options.sgf {
+            fs::write(directory.join(format!("game-{:04}.sgf", game + 1)), record.to_sgf())?;
+        }
+
+        if let Some(sprt) = &sprt {
+            let decision = sprt.decide(&stats);
+            let (lower, upper) = sprt.bounds();
+
+            println!("llr {:.3} [{:.3}, {:.3}]", sprt.llr(&stats), lower, upper);
+            if decision != SprtResult::Continue {
+                println!("sprt: {:?}", decision);
+                break;
+            }
+        }
+    }
+
+    println!("first: {}", stats);
+    first.quit()?;
+    second.quit()?;
+
+    Ok(())
+}
@@ -8,7 +8,7 @@ impl Display for Goban {
 
         write!(f, "╔═")?;
         for _ in 0..width { write!(f, "══")?; }
-        write!(f, "╗\n")?;
+        writeln!(f, "╗")?;
 
         for y in 0..height {
             write!(f, "║ ")?;
@@ -19,12 +19,12 @@ impl Display for Goban {
                     Some(Color::White) => "○ ",
                 })?;
             }
-            write!(f, "║\n")?;
+            writeln!(f, "║")?;
         }
 
         write!(f, "╚═")?;
         for _ in 0..width { write!(f, "══")?; }
-        write!(f, "╝\n")?;
+        writeln!(f, "╝")?;
 
         for _ in 0..(2 * width.saturating_sub(15)) { write!(f, " ")?; }
         write!(f, "× Black   ○ White")
@@ -1,6 +1,33 @@
 use crate::{Color, Point, array2d::Array2D, vertex::Vertex, block::Block, zobrist, ring::Ring};
 use slab::Slab;
-use std::{ops::{Index, IndexMut}, iter};
+use std::{ops::{Index, IndexMut}, iter, fmt::Display};
+
+/// The reason why a move is not legal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Illegal {
+    /// The point is not on the board.
+    OutOfBounds,
+
+    /// The point already contains a stone.
+    Occupied,
+
+    /// The stone would have no liberties, and does not capture anything.
+    Suicide,
+
+    /// The move would repeat a previous board position.
+    SuperKo,
+}
+
+impl Display for Illegal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::OutOfBounds => "point is outside of the board",
+            Self::Occupied => "point is already occupied",
+            Self::Suicide => "move is suicide",
+            Self::SuperKo => "move violates super ko",
+        })
+    }
+}
 
 #[derive(Clone)]
 pub struct Goban {
@@ -89,6 +116,12 @@ impl Goban {
         })
     }
 
+    /// Returns the Zobrist hash of the position, which does not include the
+    /// player to move or the ko history.
+    pub fn hash(&self) -> u32 {
+        self.hash
+    }
+
     /// Returns the color of the stone at the given point `at`, or `None` if
     /// the vertex is empty.
     pub fn at(&self, at: Point) -> Option<Color> {
@@ -111,6 +144,52 @@ impl Goban {
         unsafe { self.blocks.get_unchecked_mut(block) }
     }
 
+    /// Returns the number of liberties of the block that contains the stone
+    /// at `at`, or `0` if the point is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn num_liberties_at(&self, at: Point) -> usize {
+        if self[at].is_empty() || !self[at].is_valid() {
+            0
+        } else {
+            self.block_at(at).num_liberties() as usize
+        }
+    }
+
+    /// Returns the liberties of the block that contains the stone at `at`,
+    /// or an empty list if the point is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn liberties_at(&self, at: Point) -> Vec<Point> {
+        let mut liberties = vec! [];
+
+        if self[at].is_empty() || !self[at].is_valid() {
+            return liberties;
+        }
+
+        let mut curr = at;
+        loop {
+            for other in curr.neighbours() {
+                if self[other].is_valid() && self[other].is_empty() && !liberties.contains(&other) {
+                    liberties.push(other);
+                }
+            }
+
+            curr = self[curr].next_link();
+            if curr == at {
+                break
+            }
+        }
+
+        liberties
+    }
+
     fn is_super_ko(&self, hash: u32) -> bool {
         self.super_ko.contains(hash)
     }
@@ -124,23 +203,46 @@ impl Goban {
     /// * `color` -
     ///
     pub fn is_legal(&self, at: Point, color: Color) -> bool {
-        self[at].is_valid() && self[at].is_empty() && {
-            let opposite = color.opposite();
-            let mut hash = zobrist::hash(at, color);
-            let mut is_legal = false;
-
-            for other in at.neighbours() {
-                if !self[other].is_valid() {
-                    // pass
-                } else if self[other].is_empty() || (self.block_at(other).color() == color && self.block_at(other).num_liberties() >= 2) {
-                    is_legal = true;
-                } else if self.block_at(other).color() == opposite && self.block_at(other).num_liberties() == 1 {
-                    hash ^= self.block_at(other).hash();
-                    is_legal = true;
-                }
+        self.check_legal(at, color).is_ok()
+    }
+
+    /// Returns `Ok(())` if playing a stone at the given point `at` and color
+    /// `color` is a legal move according to the rules, otherwise the reason
+    /// why the move is illegal.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    /// * `color` -
+    ///
+    pub fn check_legal(&self, at: Point, color: Color) -> Result<(), Illegal> {
+        if at.x() > self.width() || at.y() > self.height() || !self[at].is_valid() {
+            return Err(Illegal::OutOfBounds);
+        } else if !self[at].is_empty() {
+            return Err(Illegal::Occupied);
+        }
+
+        let opposite = color.opposite();
+        let mut hash = zobrist::hash(at, color);
+        let mut is_legal = false;
+
+        for other in at.neighbours() {
+            if !self[other].is_valid() {
+                // pass
+            } else if self[other].is_empty() || (self.block_at(other).color() == color && self.block_at(other).num_liberties() >= 2) {
+                is_legal = true;
+            } else if self.block_at(other).color() == opposite && self.block_at(other).num_liberties() == 1 {
+                hash ^= self.block_at(other).hash();
+                is_legal = true;
             }
+        }
 
-            is_legal && !self.is_super_ko(self.hash ^ hash)
+        if !is_legal {
+            Err(Illegal::Suicide)
+        } else if self.is_super_ko(self.hash ^ hash) {
+            Err(Illegal::SuperKo)
+        } else {
+            Ok(())
         }
     }
 
@@ -167,14 +269,18 @@ impl Goban {
         self[at] = Vertex::empty(at);
     }
 
-    fn capture_at(&mut self, at: Point) -> u32 {
+    /// Remove the block that contains the stone at `at`, and returns its hash
+    /// together with the number of stones that were removed.
+    fn capture_at(&mut self, at: Point) -> (u32, usize) {
         let mut curr = at;
         let block = self[curr].block();
         let hash = self.block_by(block).hash();
+        let mut count = 0;
 
         loop {
             let next_link = self[curr].next_link();
             self.capture_single_at(curr);
+            count += 1;
             curr = next_link;
             if curr == at {
                 break
@@ -182,7 +288,7 @@ impl Goban {
         }
 
         self.blocks.remove(block);
-        hash
+        (hash, count)
     }
 
     fn is_liberty_of(&self, liberty: Point, block: usize) -> bool {
@@ -197,10 +303,8 @@ impl Goban {
 
     fn connect_single_with(&mut self, at: Point, to_block: usize) {
         for other in at.neighbours() {
-            if self[other].is_empty() && self[other].is_valid() {
-                if !self.is_liberty_of(other, to_block) {
-                    self.block_by_mut(to_block).inc_num_liberties();
-                }
+            if self[other].is_empty() && self[other].is_valid() && !self.is_liberty_of(other, to_block) {
+                self.block_by_mut(to_block).inc_num_liberties();
             }
         }
 
@@ -249,10 +353,11 @@ impl Goban {
         self.blocks.remove(a_block);
     }
 
-    fn play_update_neighbours(&mut self, at: Point, color: Color) {
+    fn play_update_neighbours(&mut self, at: Point, color: Color) -> usize {
         let opposite = color.opposite();
         let mut visited = [usize::MAX; 4];
         let mut n = 0;
+        let mut captures = 0;
 
         for other in at.neighbours() {
             if self[other].is_empty() || !self[other].is_valid() {
@@ -265,7 +370,10 @@ impl Goban {
                     n += 1;
 
                     if self.block_at(other).num_liberties() == 1 {
-                        self.hash ^= self.capture_at(other);
+                        let (hash, count) = self.capture_at(other);
+
+                        self.hash ^= hash;
+                        captures += count;
                     } else  {
                         self.block_by_mut(other_block).dec_num_liberties();
                     }
@@ -274,18 +382,21 @@ impl Goban {
                 self.connect_with(at, other);
             }
         }
+
+        captures
     }
 
-    /// Play a stone at the given vertex `at` of color `color`. This function
-    /// assumes that the given move is valid, and the result is undefined if it
-    /// is not.
+    /// Play a stone at the given vertex `at` of color `color`, and returns the
+    /// number of opponent stones that were captured. This function assumes
+    /// that the given move is valid, and the result is undefined if it is
+    /// not.
     ///
     /// # Arguments
     ///
     /// * `at` -
     /// * `color` -
     ///
-    pub fn play(&mut self, at: Point, color: Color) {
+    pub fn play(&mut self, at: Point, color: Color) -> usize {
         debug_assert!(self.is_legal(at, color));
 
         let block = self.blocks.insert(
@@ -300,8 +411,50 @@ impl Goban {
         self[at].set_block(block);
         self[at].set_next_link(at);
         self.hash ^= zobrist::hash(at, color);
-        self.play_update_neighbours(at, color);
+        let captures = self.play_update_neighbours(at, color);
         self.super_ko.insert(self.hash);
+
+        captures
+    }
+
+    /// Place a stone of the given color `color` at `at`, or remove the stone
+    /// at `at` if `color` is `None`, outside of the normal flow of the game.
+    /// Unlike `play`, this never captures anything and ignores the ko
+    /// history, but a stone can only be placed on an empty point and the
+    /// resulting position must not contain any blocks without liberties.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    /// * `color` -
+    ///
+    pub fn setup(&mut self, at: Point, color: Option<Color>) -> Result<(), Illegal> {
+        if at.x() > self.width() || at.y() > self.height() || !self[at].is_valid() {
+            return Err(Illegal::OutOfBounds);
+        } else if color.is_some() && !self[at].is_empty() {
+            return Err(Illegal::Occupied);
+        }
+
+        // rebuild the position from scratch, since the block structure can
+        // not be updated incrementally when a stone is removed. If the final
+        // position is valid then no stone placed along the way can capture
+        // anything, or be suicide.
+        let mut goban = Self::new(self.width(), self.height());
+        let stones = self.iter()
+            .filter(|&other| other != at)
+            .filter_map(|other| self.at(other).map(|c| (other, c)))
+            .chain(color.map(|c| (at, c)));
+
+        for (other, c) in stones {
+            if goban.check_legal(other, c).is_err() || goban.play(other, c) > 0 {
+                return Err(Illegal::Suicide);
+            }
+        }
+
+        goban.super_ko = self.super_ko.clone();
+        goban.super_ko.insert(goban.hash);
+        *self = goban;
+        Ok(())
     }
 
     pub fn undo(&mut self) {
@@ -408,5 +561,90 @@ mod tests {
 
         assert_eq!(goban.at(Point::new(1, 1)), None);
         assert!(!goban.is_legal(Point::new(1, 1), Color::Black));
+        assert_eq!(goban.check_legal(Point::new(1, 1), Color::Black), Err(Illegal::SuperKo));
+    }
+
+    /// ```
+    /// x x .
+    /// o x .
+    /// . . .
+    /// ```
+    #[test]
+    fn liberties_at_follows_block() {
+        let mut goban = Goban::new(3, 3);
+        goban.play((0u8, 0u8).into(), Color::Black);
+        goban.play((1u8, 0u8).into(), Color::Black);
+        goban.play((1u8, 1u8).into(), Color::Black);
+        goban.play((0u8, 1u8).into(), Color::White);
+
+        let mut liberties = goban.liberties_at((0u8, 0u8).into());
+        liberties.sort_by_key(|&at| <(u8, u8)>::from(at));
+
+        assert_eq!(liberties, vec! [(1u8, 2u8).into(), (2u8, 0u8).into(), (2u8, 1u8).into()]);
+        assert_eq!(goban.num_liberties_at((1u8, 1u8).into()), 3);
+        assert_eq!(goban.num_liberties_at((0u8, 1u8).into()), 1);
+        assert_eq!(goban.liberties_at((0u8, 1u8).into()), vec! [(0u8, 2u8).into()]);
+        assert_eq!(goban.num_liberties_at((2u8, 2u8).into()), 0);
+    }
+
+    /// ```
+    /// . x
+    /// x
+    /// ```
+    #[test]
+    fn check_legal_returns_reason() {
+        let mut goban = Goban::new(9, 9);
+        goban.play(Point::new(2, 1), Color::Black);
+        goban.play(Point::new(1, 2), Color::Black);
+
+        assert_eq!(goban.check_legal(Point::new(1, 1), Color::Black), Ok(()));
+        assert_eq!(goban.check_legal(Point::new(1, 1), Color::White), Err(Illegal::Suicide));
+        assert_eq!(goban.check_legal(Point::new(2, 1), Color::White), Err(Illegal::Occupied));
+        assert_eq!(goban.check_legal(Point::new(10, 1), Color::White), Err(Illegal::OutOfBounds));
+    }
+
+    /// ```
+    /// o x
+    /// x
+    /// ```
+    #[test]
+    fn play_returns_number_of_captures() {
+        let mut goban = Goban::new(9, 9);
+
+        assert_eq!(goban.play(Point::new(1, 1), Color::White), 0);
+        assert_eq!(goban.play(Point::new(1, 2), Color::Black), 0);
+        assert_eq!(goban.play(Point::new(2, 1), Color::Black), 1);
+    }
+
+    /// ```
+    /// x . x
+    /// ```
+    #[test]
+    fn setup_removes_stone_from_block() {
+        let mut goban = Goban::new(9, 9);
+        goban.play(Point::new(1, 1), Color::Black);
+        goban.play(Point::new(2, 1), Color::Black);
+        goban.play(Point::new(3, 1), Color::Black);
+
+        assert_eq!(goban.setup(Point::new(2, 1), None), Ok(()));
+        assert_eq!(goban.at(Point::new(2, 1)), None);
+        assert_eq!(goban.num_liberties_at(Point::new(1, 1)), 2);
+        assert_eq!(goban.num_liberties_at(Point::new(3, 1)), 3);
+    }
+
+    /// ```
+    /// o x
+    /// x
+    /// ```
+    #[test]
+    fn setup_does_not_capture() {
+        let mut goban = Goban::new(9, 9);
+
+        assert_eq!(goban.setup(Point::new(1, 1), Some(Color::White)), Ok(()));
+        assert_eq!(goban.setup(Point::new(2, 1), Some(Color::Black)), Ok(()));
+        assert_eq!(goban.setup(Point::new(1, 2), Some(Color::Black)), Err(Illegal::Suicide));
+        assert_eq!(goban.setup(Point::new(1, 1), Some(Color::Black)), Err(Illegal::Occupied));
+        assert_eq!(goban.at(Point::new(1, 1)), Some(Color::White));
+        assert_eq!(goban.at(Point::new(1, 2)), None);
     }
 }
@@ -0,0 +1,230 @@
+use crate::{Color, score::{MoveAnalysis, Search}};
+use super::{format_vertex, parse_color};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
+
+/// The dialect of the analysis output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalyzeKind {
+    /// `lz-analyze`, as understood by Leela Zero, where win rates are integers
+    /// in the range `[0, 10000]`.
+    Leela,
+
+    /// `kata-analyze`, as understood by KataGo, where win rates are in the
+    /// range `[0, 1]` and the ownership can be requested.
+    Kata,
+}
+
+/// The arguments of an `lz-analyze` or `kata-analyze` command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analyze {
+    kind: AnalyzeKind,
+    to_move: Color,
+    interval: Duration,
+    ownership: bool,
+    min_moves: usize,
+    max_moves: usize,
+}
+
+/// The longest time the search runs without checking for a new command, or
+/// whether it is time to write the next `info` line.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+impl Analyze {
+    /// Returns the parsed arguments `[color] [interval] [key value]*` of an
+    /// analysis command, where `interval` is given in centiseconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` -
+    /// * `to_move` - the color to analyze for, if no color is given
+    /// * `args` -
+    ///
+    pub fn parse(kind: AnalyzeKind, to_move: Color, args: &[&str]) -> Result<Self, String> {
+        let mut analyze = Self {
+            kind,
+            to_move,
+            interval: Duration::from_secs(1),
+            ownership: false,
+            min_moves: 0,
+            max_moves: usize::MAX,
+        };
+        let mut args = args.iter().peekable();
+        let parse_interval = |arg: &str| arg.parse::<u64>().map(|cs| Duration::from_millis(10 * cs.max(1)));
+
+        if let Some(color) = args.peek().and_then(|arg| parse_color(arg)) {
+            analyze.to_move = color;
+            args.next();
+        }
+
+        if let Some(interval) = args.peek().and_then(|arg| parse_interval(arg).ok()) {
+            analyze.interval = interval;
+            args.next();
+        }
+
+        while let Some(key) = args.next() {
+            let value = args.next().ok_or("syntax error")?;
+
+            match *key {
+                "interval" => { analyze.interval = parse_interval(value).map_err(|_| "syntax error")? },
+                "ownership" if kind == AnalyzeKind::Kata => { analyze.ownership = value.parse().map_err(|_| "syntax error")? },
+                "maxmoves" => { analyze.max_moves = value.parse().map_err(|_| "syntax error")? },
+                "minmoves" => { analyze.min_moves = value.parse().map_err(|_| "syntax error")? },
+                _ => { return Err("syntax error".into()) }
+            }
+        }
+
+        Ok(analyze)
+    }
+
+    /// Returns the color to analyze for.
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    fn format_candidate(&self, f: &mut String, order: usize, candidate: &MoveAnalysis, height: usize) {
+        let stderr = (candidate.variance() / candidate.visits().max(1) as f32).sqrt();
+        let lcb = (candidate.mean() - 1.96 * stderr).max(0.0);
+        let pv = candidate.pv().iter()
+            .map(|&at| format_vertex(at, height))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let _ = match self.kind {
+            AnalyzeKind::Leela => write!(
+                f,
+                "info move {} visits {} winrate {} prior {} lcb {} order {} pv {} ",
+                format_vertex(candidate.at(), height),
+                candidate.visits(),
+                (10000.0 * candidate.mean()).round() as i32,
+                (10000.0 * candidate.prior()).round() as i32,
+                (10000.0 * lcb).round() as i32,
+                order,
+                pv,
+            ),
+            AnalyzeKind::Kata => write!(
+                f,
+                "info move {} visits {} winrate {:.6} prior {:.6} lcb {:.6} order {} pv {} ",
+                format_vertex(candidate.at(), height),
+                candidate.visits(),
+                candidate.mean(),
+                candidate.prior(),
+                lcb,
+                order,
+                pv,
+            ),
+        };
+    }
+
+    /// Returns the `info` line for the current state of the given search
+    /// `search`, which reports every candidate that has been visited, and at
+    /// least `minmoves` candidates.
+    fn format_info(&self, search: &Search) -> String {
+        let mut f = String::new();
+        let height = search.state().goban().height();
+
+        let analysis = search.analysis();
+        let candidates = analysis.moves().iter()
+            .enumerate()
+            .filter(|&(i, candidate)| candidate.visits() > 0 || i < self.min_moves)
+            .map(|(_, candidate)| candidate);
+
+        for (order, candidate) in candidates.take(self.max_moves).enumerate() {
+            self.format_candidate(&mut f, order, candidate, height);
+        }
+
+        if self.ownership {
+            let sign = if self.to_move == Color::Black { 1.0 } else { -1.0 };
+
+            f.push_str("ownership");
+            for value in search.ownership() {
+                let _ = write!(f, " {:.4}", sign * value);
+            }
+        }
+
+        f.trim_end().to_string()
+    }
+
+    /// Continue the given search `search`, which should be of the position to
+    /// analyze with the color to analyze for to move, and write an `info` line
+    /// to `output` every interval, until a new command is received through
+    /// `input`. Returns the command that interrupted the analysis, or `None`
+    /// if the input was closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` -
+    /// * `input` -
+    /// * `output` -
+    ///
+    pub fn run(
+        &self,
+        search: &mut Search,
+        input: &Receiver<io::Result<String>>,
+        output: &mut impl Write,
+    ) -> io::Result<Option<io::Result<String>>>
+    {
+        let mut next_info = Instant::now() + self.interval;
+
+        loop {
+            search.run_for(next_info.saturating_duration_since(Instant::now()).min(POLL_INTERVAL));
+
+            if Instant::now() >= next_info {
+                writeln!(output, "{}", self.format_info(search))?;
+                output.flush()?;
+                next_info = Instant::now() + self.interval;
+            }
+
+            match input.try_recv() {
+                Ok(line) => { return Ok(Some(line)) },
+                Err(TryRecvError::Disconnected) => { return Ok(None) },
+                Err(TryRecvError::Empty) => {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Goban, score::{GameState, ScoreOptions}};
+    use std::sync::mpsc;
+
+    #[test]
+    fn parse_reads_positional_and_keyword_arguments() {
+        let analyze = Analyze::parse(AnalyzeKind::Kata, Color::Black, &["w", "50", "ownership", "true", "maxmoves", "3"]).unwrap();
+
+        assert_eq!(analyze.to_move, Color::White);
+        assert_eq!(analyze.interval, Duration::from_millis(500));
+        assert!(analyze.ownership);
+        assert_eq!(analyze.max_moves, 3);
+
+        let analyze = Analyze::parse(AnalyzeKind::Leela, Color::White, &["interval", "10"]).unwrap();
+
+        assert_eq!(analyze.to_move, Color::White);
+        assert_eq!(analyze.interval, Duration::from_millis(100));
+        assert!(Analyze::parse(AnalyzeKind::Leela, Color::White, &["ownership", "true"]).is_err());
+    }
+
+    #[test]
+    fn run_reports_at_least_min_moves() {
+        let analyze = Analyze::parse(AnalyzeKind::Kata, Color::Black, &["1", "minmoves", "30", "ownership", "true"]).unwrap();
+        let options = ScoreOptions::new().with_max_sims(1);
+        let mut search = Search::new(GameState::new(Goban::new(5, 5), Color::Black), options);
+        let (sender, receiver) = mpsc::channel();
+        let mut output = vec! [];
+        sender.send(Ok("name".into())).unwrap();
+
+        assert_eq!(analyze.run(&mut search, &receiver, &mut output).unwrap().unwrap().unwrap(), "name");
+        assert!(search.total_sims() > 1);
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.matches("info move ").count(), search.analysis().moves().len(), "{}", output);
+        assert_eq!(output.split(" ownership ").nth(1).unwrap().split_whitespace().count(), 25, "{}", output);
+    }
+}
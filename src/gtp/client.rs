@@ -0,0 +1,482 @@
+use crate::{
+    Color, Goban, Illegal, Point,
+    record::{GameRecord, Move},
+};
+use super::{format_color, format_vertex, parse_vertex};
+use std::{
+    fmt::Display,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// The error returned when a command sent to an engine did not succeed.
+#[derive(Debug)]
+pub enum ClientError {
+    /// Reading from, or writing to, the engine failed.
+    Io(io::Error),
+
+    /// The engine responded with the given error message.
+    Failure(String),
+
+    /// The engine sent the given response, which is not valid.
+    Protocol(String),
+
+    /// The given move is illegal in the mirrored position, for the given
+    /// reason.
+    Illegal(Move, Illegal),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Failure(message) => write!(f, "engine error: {}", message),
+            Self::Protocol(response) => write!(f, "invalid response: {}", response),
+            Self::Illegal(mv, reason) => write!(f, "illegal move {:?} at {:?}: {}", mv.color(), mv.at(), reason),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    // pass
+}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The controller side of the Go Text Protocol (version 2), that sends
+/// commands to an engine and keeps a mirror of the game on a `Goban`. Every
+/// move is checked against the mirror before it is sent to, or accepted
+/// from, the engine.
+pub struct Client<R: BufRead, W: Write> {
+    reader: R,
+    writer: W,
+    child: Option<Child>,
+    record: GameRecord,
+    goban: Goban,
+    next_id: u32,
+}
+
+impl Client<BufReader<ChildStdout>, ChildStdin> {
+    /// Returns a client for the engine started by the given command, which
+    /// communicates through its standard input and output.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` -
+    ///
+    pub fn spawn(mut command: Command) -> Result<Self, ClientError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let reader = BufReader::new(child.stdout.take().ok_or_else(|| io::Error::other("no stdout"))?);
+        let writer = child.stdin.take().ok_or_else(|| io::Error::other("no stdin"))?;
+        let mut client = Self::new(reader, writer);
+        client.child = Some(child);
+
+        Ok(client)
+    }
+}
+
+impl<R: BufRead, W: Write> Drop for Client<R, W> {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Client<R, W> {
+    /// Returns a client for the engine that reads commands from `writer` and
+    /// writes its responses to `reader`. The mirror starts out as an empty
+    /// `19x19` board without komi.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` -
+    /// * `writer` -
+    ///
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            child: None,
+            record: GameRecord::new(19, 19),
+            goban: Goban::new(19, 19),
+            next_id: 1,
+        }
+    }
+
+    /// Returns the mirrored position.
+    pub fn goban(&self) -> &Goban {
+        &self.goban
+    }
+
+    /// Returns the record of the mirrored game.
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// Returns the next response from the engine, together with its id (if
+    /// any). Multi-line responses are joined with newlines.
+    fn read_response(&mut self) -> Result<(Option<u32>, Result<String, String>), ClientError> {
+        let mut lines = vec! [];
+
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+
+            let line = line.replace('\r', "").replace('\t', " ");
+            let line = line.trim_end_matches('\n');
+
+            if line.trim().is_empty() {
+                if !lines.is_empty() {
+                    break;
+                }
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+
+        let first = lines[0].as_str();
+        let (is_success, rest) = match first.as_bytes()[0] {
+            b'=' => (true, &first[1..]),
+            b'?' => (false, &first[1..]),
+            _ => { return Err(ClientError::Protocol(lines.join("\n"))) }
+        };
+        let id_len = rest.bytes().take_while(|ch| ch.is_ascii_digit()).count();
+        let id = rest[..id_len].parse::<u32>().ok();
+        let mut text = rest[id_len..].trim().to_string();
+
+        for line in &lines[1..] {
+            text.push('\n');
+            text.push_str(line);
+        }
+
+        Ok((id, if is_success { Ok(text) } else { Err(text) }))
+    }
+
+    /// Send the given command `command`, e.g. `boardsize 9`, to the engine
+    /// and returns its response. The mirror is not updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` -
+    ///
+    pub fn send(&mut self, command: &str) -> Result<String, ClientError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        writeln!(self.writer, "{} {}", id, command)?;
+        self.writer.flush()?;
+
+        match self.read_response()? {
+            (Some(other), _) if other != id => Err(ClientError::Protocol(format!("expected id {}, got {}", id, other))),
+            (_, Ok(text)) => Ok(text),
+            (_, Err(message)) => Err(ClientError::Failure(message)),
+        }
+    }
+
+    /// Returns the name of the engine.
+    pub fn name(&mut self) -> Result<String, ClientError> {
+        self.send("name")
+    }
+
+    /// Returns the version of the engine.
+    pub fn version(&mut self) -> Result<String, ClientError> {
+        self.send("version")
+    }
+
+    /// Returns whether the engine understands the command with the given name
+    /// `name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` -
+    ///
+    pub fn known_command(&mut self, name: &str) -> Result<bool, ClientError> {
+        Ok(self.send(&format!("known_command {}", name))? == "true")
+    }
+
+    /// Change the size of the board, which also clears it.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` -
+    ///
+    pub fn boardsize(&mut self, size: usize) -> Result<(), ClientError> {
+        self.send(&format!("boardsize {}", size))?;
+        self.reset(size, size);
+
+        Ok(())
+    }
+
+    /// Remove all stones from the board.
+    pub fn clear_board(&mut self) -> Result<(), ClientError> {
+        self.send("clear_board")?;
+        self.reset(self.record.width(), self.record.height());
+
+        Ok(())
+    }
+
+    fn reset(&mut self, width: usize, height: usize) {
+        let mut record = GameRecord::new(width, height);
+        record.set_komi(self.record.komi());
+
+        self.record = record;
+        self.goban = Goban::new(width, height);
+    }
+
+    pub fn komi(&mut self, komi: f32) -> Result<(), ClientError> {
+        self.send(&format!("komi {}", komi))?;
+        self.record.set_komi(komi);
+
+        Ok(())
+    }
+
+    fn mirror_move(&mut self, mv: Move) -> Result<(), ClientError> {
+        if let Some(at) = mv.at() {
+            self.goban.check_legal(at, mv.color()).map_err(|reason| ClientError::Illegal(mv, reason))?;
+            self.goban.play(at, mv.color());
+        }
+
+        self.record.push_move(mv);
+        Ok(())
+    }
+
+    /// Play the given move `mv`, which must be legal in the mirrored position.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` -
+    ///
+    pub fn play(&mut self, mv: Move) -> Result<(), ClientError> {
+        if let Some(at) = mv.at() {
+            self.goban.check_legal(at, mv.color()).map_err(|reason| ClientError::Illegal(mv, reason))?;
+        }
+
+        self.send(&format!("play {} {}", format_color(mv.color()), format_vertex(mv.at(), self.goban.height())))?;
+        self.mirror_move(mv)
+    }
+
+    /// Returns the move generated by the engine for the given color `color`,
+    /// after it has been played in the mirrored position, or `None` if the
+    /// engine resigned.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    ///
+    pub fn genmove(&mut self, color: Color) -> Result<Option<Move>, ClientError> {
+        let response = self.send(&format!("genmove {}", format_color(color)))?;
+
+        if response.eq_ignore_ascii_case("resign") {
+            return Ok(None);
+        }
+
+        let mv = match parse_vertex(&response, self.goban.width(), self.goban.height()) {
+            Some(Some(at)) => Move::play(color, at),
+            Some(None) => Move::pass(color),
+            None => { return Err(ClientError::Protocol(response)) }
+        };
+
+        self.mirror_move(mv)?;
+        Ok(Some(mv))
+    }
+
+    /// Take back the last move.
+    pub fn undo(&mut self) -> Result<(), ClientError> {
+        self.send("undo")?;
+
+        if self.record.pop_move().is_some() {
+            self.goban = self.record.replay().play_to_end()
+                .map_err(|err| ClientError::Protocol(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the handicap stones placed by the engine, when asked to place
+    /// `count` fixed handicap stones.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` -
+    ///
+    pub fn fixed_handicap(&mut self, count: usize) -> Result<Vec<Point>, ClientError> {
+        let response = self.send(&format!("fixed_handicap {}", count))?;
+        let points = response.split_whitespace()
+            .map(|vertex| match parse_vertex(vertex, self.goban.width(), self.goban.height()) {
+                Some(Some(at)) => Ok(at),
+                _ => Err(ClientError::Protocol(response.clone())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for &at in &points {
+            let mv = Move::play(Color::Black, at);
+
+            self.goban.check_legal(at, Color::Black).map_err(|reason| ClientError::Illegal(mv, reason))?;
+            self.goban.play(at, Color::Black);
+            self.record.push_setup(Color::Black, at);
+        }
+
+        self.record.set_handicap(points.len());
+        Ok(points)
+    }
+
+    /// Returns the score of the game according to the engine, e.g. `B+3.5`.
+    pub fn final_score(&mut self) -> Result<String, ClientError> {
+        self.send("final_score")
+    }
+
+    /// Tell the engine to exit, and wait for it to do so.
+    pub fn quit(mut self) -> Result<(), ClientError> {
+        self.send("quit")?;
+
+        if let Some(mut child) = self.child.take() {
+            child.wait()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The reading end of an in-memory pipe, see `pipe`.
+#[cfg(test)]
+pub(super) struct PipeReader {
+    receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+#[cfg(test)]
+impl io::Read for PipeReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.buf.len() {
+            match self.receiver.recv() {
+                Ok(buf) => {
+                    self.buf = buf;
+                    self.pos = 0;
+                },
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// The writing end of an in-memory pipe, see `pipe`.
+#[cfg(test)]
+pub(super) struct PipeWriter {
+    sender: std::sync::mpsc::Sender<Vec<u8>>,
+}
+
+#[cfg(test)]
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() {
+            self.sender.send(buf.to_vec()).map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the two ends of an in-memory pipe, where the reader reaches the
+/// end of the stream once the writer is dropped.
+#[cfg(test)]
+pub(super) fn pipe() -> (PipeReader, PipeWriter) {
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    (PipeReader { receiver, buf: vec! [], pos: 0 }, PipeWriter { sender })
+}
+
+/// Returns a client connected to a local `Engine` running on a separate
+/// thread.
+#[cfg(test)]
+pub(super) fn stand_in() -> Client<BufReader<PipeReader>, PipeWriter> {
+    let (command_reader, command_writer) = pipe();
+    let (response_reader, response_writer) = pipe();
+
+    std::thread::spawn(move || {
+        super::Engine::new().run(BufReader::new(command_reader), response_writer).unwrap();
+    });
+
+    Client::new(BufReader::new(response_reader), command_writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_response_joins_lines() {
+        let mut client = Client::new(Cursor::new("\n=3 first\r\nsecond\n\n?4  oops\n\n"), vec! []);
+
+        assert_eq!(client.read_response().unwrap(), (Some(3), Ok("first\nsecond".into())));
+        assert_eq!(client.read_response().unwrap(), (Some(4), Err("oops".into())));
+        assert!(matches!(client.read_response(), Err(ClientError::Io(_))));
+    }
+
+    #[test]
+    fn send_reports_failures() {
+        let mut client = stand_in();
+
+        assert_eq!(client.name().unwrap(), "gobit");
+        assert!(client.known_command("genmove").unwrap());
+        assert!(matches!(client.send("foo"), Err(ClientError::Failure(message)) if message == "unknown command"));
+        client.quit().unwrap();
+    }
+
+    #[test]
+    fn play_keeps_mirror_in_sync() {
+        let mut client = stand_in();
+        let at = Point::from((2usize, 2usize));
+
+        client.boardsize(5).unwrap();
+        client.play(Move::play(Color::Black, at)).unwrap();
+
+        assert_eq!(client.goban().at(at), Some(Color::Black));
+        assert!(matches!(
+            client.play(Move::play(Color::White, at)),
+            Err(ClientError::Illegal(_, Illegal::Occupied))
+        ));
+
+        let mv = client.genmove(Color::White).unwrap().unwrap();
+        if let Some(at) = mv.at() {
+            assert_eq!(client.goban().at(at), Some(Color::White));
+        }
+        assert_eq!(client.record().moves().count(), 2);
+
+        client.undo().unwrap();
+        client.undo().unwrap();
+        assert_eq!(client.goban().at(at), None);
+        assert_eq!(client.record().moves().count(), 0);
+    }
+
+    #[test]
+    fn fixed_handicap_is_mirrored() {
+        let mut client = stand_in();
+        let points = client.fixed_handicap(2).unwrap();
+
+        assert_eq!(points, vec! [(3usize, 15usize).into(), (15usize, 3usize).into()]);
+        assert_eq!(client.goban().at(points[0]), Some(Color::Black));
+        assert_eq!(client.record().handicap(), 2);
+    }
+}
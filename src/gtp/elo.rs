@@ -0,0 +1,271 @@
+use std::fmt::Display;
+
+/// Returns the expected score of a player that is `elo` points stronger than
+/// its opponent.
+///
+/// # Arguments
+///
+/// * `elo` -
+///
+fn expected_score(elo: f32) -> f32 {
+    1.0 / (1.0 + 10.0f32.powf(-elo / 400.0))
+}
+
+/// Returns the elo difference that corresponds to the expected score `score`.
+///
+/// # Arguments
+///
+/// * `score` -
+///
+fn elo_of(score: f32) -> f32 {
+    if score <= 0.0 {
+        f32::NEG_INFINITY
+    } else if score >= 1.0 {
+        f32::INFINITY
+    } else {
+        400.0 * (score / (1.0 - score)).log10()
+    }
+}
+
+/// The number of wins, draws, and losses of one player in a match.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchStats {
+    wins: u32,
+    draws: u32,
+    losses: u32,
+}
+
+impl Display for MatchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (lower, upper) = self.elo_interval(1.96);
+
+        write!(
+            f,
+            "{} games, +{} ={} -{}, score {:.1}%, elo {:.1} [{:.1}, {:.1}]",
+            self.games(),
+            self.wins,
+            self.draws,
+            self.losses,
+            100.0 * self.score(),
+            self.elo(),
+            lower,
+            upper
+        )
+    }
+}
+
+impl MatchStats {
+    pub fn new(wins: u32, draws: u32, losses: u32) -> Self {
+        Self { wins, draws, losses }
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    pub fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Record the outcome of a single game, where `Some(true)` is a win,
+    /// `Some(false)` a loss, and `None` a draw.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_win` -
+    ///
+    pub fn push(&mut self, is_win: Option<bool>) {
+        match is_win {
+            Some(true) => { self.wins += 1 },
+            Some(false) => { self.losses += 1 },
+            None => { self.draws += 1 },
+        }
+    }
+
+    /// Returns the average score per game, where a draw counts as half a
+    /// win.
+    pub fn score(&self) -> f32 {
+        if self.games() == 0 {
+            0.5
+        } else {
+            (self.wins as f32 + 0.5 * self.draws as f32) / self.games() as f32
+        }
+    }
+
+    /// Returns the variance of the score of a single game.
+    fn variance(&self) -> f32 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+
+        let score = self.score();
+        let n = self.games() as f32;
+
+        (
+            self.wins as f32 * (1.0 - score).powi(2)
+            + self.draws as f32 * (0.5 - score).powi(2)
+            + self.losses as f32 * score.powi(2)
+        ) / n
+    }
+
+    /// Returns the estimated elo difference to the opponent.
+    pub fn elo(&self) -> f32 {
+        elo_of(self.score())
+    }
+
+    /// Returns the confidence interval of the elo difference, where `z` is
+    /// the number of standard deviations, e.g. `1.96` for 95%.
+    ///
+    /// # Arguments
+    ///
+    /// * `z` -
+    ///
+    pub fn elo_interval(&self, z: f32) -> (f32, f32) {
+        let std_err = (self.variance() / self.games().max(1) as f32).sqrt();
+        let score = self.score();
+
+        (elo_of(score - z * std_err), elo_of(score + z * std_err))
+    }
+}
+
+/// The decision of a sequential probability ratio test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtResult {
+    /// The player is not stronger than `elo0`.
+    AcceptH0,
+
+    /// The player is at least as strong as `elo1`.
+    AcceptH1,
+
+    /// More games are needed to decide.
+    Continue,
+}
+
+/// A sequential probability ratio test, that decides between the hypothesis
+/// that the elo difference is `elo0` (H0) or `elo1` (H1) with the given
+/// error rates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprt {
+    elo0: f32,
+    elo1: f32,
+    alpha: f32,
+    beta: f32,
+}
+
+impl Sprt {
+    /// Returns a test between `elo0` and `elo1`, where `alpha` is the
+    /// probability of a false positive, and `beta` the probability of a false
+    /// negative.
+    ///
+    /// # Arguments
+    ///
+    /// * `elo0` -
+    /// * `elo1` -
+    /// * `alpha` -
+    /// * `beta` -
+    ///
+    pub fn new(elo0: f32, elo1: f32, alpha: f32, beta: f32) -> Self {
+        Self { elo0, elo1, alpha, beta }
+    }
+
+    /// Returns the lower and upper bound of the log-likelihood ratio.
+    pub fn bounds(&self) -> (f32, f32) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Returns the (approximate) log-likelihood ratio of H1 over H0, given
+    /// the results so far. If every game had the same outcome, then the
+    /// variance of the results is zero, so the variance of a game between
+    /// players that are halfway between the two hypotheses is used instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` -
+    ///
+    pub fn llr(&self, stats: &MatchStats) -> f32 {
+        if stats.games() == 0 {
+            return 0.0;
+        }
+
+        let score0 = expected_score(self.elo0);
+        let score1 = expected_score(self.elo1);
+        let variance = match stats.variance() {
+            variance if variance > 0.0 => variance,
+            _ => {
+                let score = 0.5 * (score0 + score1);
+
+                score * (1.0 - score)
+            },
+        };
+
+        stats.games() as f32 * (score1 - score0) * (2.0 * stats.score() - score0 - score1) / (2.0 * variance)
+    }
+
+    /// Returns the decision of this test, given the results so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` -
+    ///
+    pub fn decide(&self, stats: &MatchStats) -> SprtResult {
+        let llr = self.llr(stats);
+        let (lower, upper) = self.bounds();
+
+        if llr >= upper {
+            SprtResult::AcceptH1
+        } else if llr <= lower {
+            SprtResult::AcceptH0
+        } else {
+            SprtResult::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elo_matches_score() {
+        assert_eq!(MatchStats::default().elo(), 0.0);
+        assert!((MatchStats::new(3, 0, 1).elo() - 190.85).abs() < 0.1);
+        assert!((MatchStats::new(1, 2, 3).elo() + 120.41).abs() < 0.1);
+
+        let (lower, upper) = MatchStats::new(60, 0, 40).elo_interval(1.96);
+        let elo = MatchStats::new(60, 0, 40).elo();
+
+        assert!(lower < elo && elo < upper);
+        assert!(lower > 0.0 && upper > 140.0);
+    }
+
+    #[test]
+    fn sprt_decides_clear_results() {
+        let sprt = Sprt::new(0.0, 20.0, 0.05, 0.05);
+
+        assert_eq!(sprt.decide(&MatchStats::new(10, 0, 10)), SprtResult::Continue);
+        assert_eq!(sprt.decide(&MatchStats::new(700, 0, 300)), SprtResult::AcceptH1);
+        assert_eq!(sprt.decide(&MatchStats::new(300, 0, 700)), SprtResult::AcceptH0);
+    }
+
+    #[test]
+    fn sprt_decides_one_sided_results() {
+        let sprt = Sprt::new(0.0, 50.0, 0.05, 0.05);
+
+        assert_eq!(sprt.llr(&MatchStats::default()), 0.0);
+        assert_eq!(sprt.decide(&MatchStats::new(40, 0, 0)), SprtResult::AcceptH1);
+        assert_eq!(sprt.decide(&MatchStats::new(0, 0, 40)), SprtResult::AcceptH0);
+        assert!(sprt.llr(&MatchStats::new(40, 0, 0)) > sprt.llr(&MatchStats::new(20, 0, 0)));
+        assert!(sprt.llr(&MatchStats::new(0, 40, 0)) < 0.0);
+    }
+}
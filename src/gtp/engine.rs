@@ -0,0 +1,697 @@
+use crate::{
+    Color, Goban, Point,
+    record::{fixed_handicap, GameRecord, Move},
+    score::{GameState, Rules, Score, ScoreOptions, Search},
+    sgf,
+};
+use super::{format_color, format_vertex, parse_color, parse_vertex, Analyze, AnalyzeKind};
+use std::{fs, io::{self, BufRead, Write}, sync::mpsc, thread, time::Duration};
+
+/// The commands understood by the engine, in the order they are listed by
+/// `list_commands`.
+const COMMANDS: &[&str] = &[
+    "boardsize",
+    "clear_board",
+    "final_score",
+    "final_status_list",
+    "fixed_handicap",
+    "genmove",
+    "known_command",
+    "kata-analyze",
+    "komi",
+    "list_commands",
+    "loadsgf",
+    "lz-analyze",
+    "name",
+    "play",
+    "protocol_version",
+    "quit",
+    "set_free_handicap",
+    "showboard",
+    "time_left",
+    "time_settings",
+    "undo",
+    "version",
+];
+
+/// The time settings of the game, as given by `time_settings`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimeSettings {
+    main_time: u32,
+    byo_yomi_time: u32,
+    byo_yomi_stones: u32,
+}
+
+impl TimeSettings {
+    /// Returns the main time in seconds.
+    pub fn main_time(&self) -> u32 {
+        self.main_time
+    }
+
+    /// Returns the length of each byo-yomi period in seconds.
+    pub fn byo_yomi_time(&self) -> u32 {
+        self.byo_yomi_time
+    }
+
+    /// Returns the number of stones that must be played in each byo-yomi
+    /// period, where `0` means there is no byo-yomi.
+    pub fn byo_yomi_stones(&self) -> u32 {
+        self.byo_yomi_stones
+    }
+
+    /// Returns how long to think about the next move on a board with the
+    /// given number of points `num_points`, or `None` if there is no time
+    /// limit. The main time is spread over the moves that are expected to
+    /// remain, while in byo-yomi the period is spread over its stones.
+    ///
+    /// Following GTP, a byo-yomi time without stones means there are no time
+    /// limits, and so does a main time and byo-yomi time of zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_left` - the time left in seconds and the number of stones
+    ///   left in the current period, as given by `time_left`, if any
+    /// * `num_points` -
+    ///
+    pub fn time_for_move(&self, time_left: Option<(u32, u32)>, num_points: usize) -> Option<Duration> {
+        if (self.main_time == 0 && self.byo_yomi_time == 0) || (self.byo_yomi_time > 0 && self.byo_yomi_stones == 0) {
+            return None;
+        }
+
+        let (time, stones) = time_left.unwrap_or(if self.main_time > 0 {
+            (self.main_time, 0)
+        } else {
+            (self.byo_yomi_time, self.byo_yomi_stones)
+        });
+        let seconds = if stones > 0 {
+            time as f32 / stones as f32
+        } else {
+            let byo_yomi = if self.byo_yomi_stones > 0 { self.byo_yomi_time as f32 / self.byo_yomi_stones as f32 } else { 0.0 };
+
+            time as f32 / (num_points / 3).max(10) as f32 + byo_yomi
+        };
+
+        // keep a safety margin for the communication with the controller
+        Some(Duration::from_secs_f32(0.9 * seconds))
+    }
+}
+
+/// A Go Text Protocol (version 2) engine, that keeps track of the game using
+/// a `Goban` and generates moves using a `Search` that is kept between moves.
+pub struct Engine {
+    record: GameRecord,
+    goban: Goban,
+    time_settings: TimeSettings,
+    black_time_left: Option<(u32, u32)>,
+    white_time_left: Option<(u32, u32)>,
+    analyze: Option<Analyze>,
+    num_threads: usize,
+    search: Option<Search>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    /// Returns an engine with an empty `19x19` board and `7.5` komi, that
+    /// searches using every available core.
+    pub fn new() -> Self {
+        let mut record = GameRecord::new(19, 19);
+        record.set_komi(7.5);
+
+        Self {
+            goban: Goban::new(19, 19),
+            record,
+            time_settings: TimeSettings::default(),
+            black_time_left: None,
+            white_time_left: None,
+            analyze: None,
+            num_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            search: None,
+        }
+    }
+
+    /// Set the number of threads used by the search.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_threads` -
+    ///
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Returns the current position.
+    pub fn goban(&self) -> &Goban {
+        &self.goban
+    }
+
+    /// Returns the record of the current game.
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// Returns the time settings of the current game.
+    pub fn time_settings(&self) -> TimeSettings {
+        self.time_settings
+    }
+
+    /// Read commands from `input` and write the responses to `output` until
+    /// either `quit` is received or the input is exhausted. The input is read
+    /// on a separate thread, so that an ongoing analysis can be interrupted
+    /// by the next command.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` -
+    /// * `output` -
+    ///
+    pub fn run(&mut self, input: impl BufRead + Send + 'static, mut output: impl Write) -> io::Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        let mut next_line = None;
+
+        thread::spawn(move || {
+            for line in input.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        while let Some(line) = next_line.take().or_else(|| receiver.recv().ok()) {
+            if let Some((response, is_quit)) = self.execute(&line?) {
+                output.write_all(response.as_bytes())?;
+                output.flush()?;
+
+                if is_quit {
+                    break;
+                }
+            }
+
+            if let Some(analyze) = self.analyze.take() {
+                let state = self.state().map_err(io::Error::other)?.with_to_move(analyze.to_move());
+
+                next_line = analyze.run(self.search_of(state), &receiver, &mut output)?;
+                output.write_all(b"\n")?;
+                output.flush()?;
+
+                if next_line.is_none() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the full response to the given command line `line`, including
+    /// the terminating empty line, and whether the engine should exit. Returns
+    /// `None` if the line does not contain a command.
+    ///
+    /// The response to `lz-analyze` and `kata-analyze` is not terminated, since
+    /// the analysis itself is only performed by `run`.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` -
+    ///
+    pub fn execute(&mut self, line: &str) -> Option<(String, bool)> {
+        let line = line.split('#').next().unwrap_or("");
+        let line = line.chars()
+            .filter(|&ch| ch == '\t' || !ch.is_control())
+            .map(|ch| if ch == '\t' { ' ' } else { ch })
+            .collect::<String>();
+        let mut words = line.split_whitespace().peekable();
+        let id = words.next_if(|word| word.parse::<u32>().is_ok()).unwrap_or("");
+        let name = words.next()?;
+        let args = words.collect::<Vec<_>>();
+
+        Some(match self.handle(name, &args) {
+            Ok(_) if self.analyze.is_some() => (format!("={}\n", id), false),
+            Ok(response) => (format!("={} {}\n\n", id, response), name == "quit"),
+            Err(message) => (format!("?{} {}\n\n", id, message), false),
+        })
+    }
+
+    fn handle(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match name {
+            "protocol_version" => Ok("2".into()),
+            "name" => Ok("gobit".into()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").into()),
+            "known_command" => Ok(COMMANDS.contains(args.first().unwrap_or(&"")).to_string()),
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "quit" => Ok("".into()),
+            "boardsize" => self.boardsize(args),
+            "clear_board" => {
+                self.clear_board(self.record.width(), self.record.height());
+                Ok("".into())
+            },
+            "komi" => {
+                let komi = parse_arg::<f32>(args, 0)?;
+                self.record.set_komi(komi);
+                Ok("".into())
+            },
+            "play" => self.play(args),
+            "genmove" => self.genmove(args),
+            "undo" => self.undo(),
+            "showboard" => Ok(format!("\n{}", self.goban)),
+            "fixed_handicap" => self.fixed_handicap(args),
+            "set_free_handicap" => self.set_free_handicap(args),
+            "final_score" => self.final_score(),
+            "final_status_list" => self.final_status_list(args),
+            "loadsgf" => self.loadsgf(args),
+            "lz-analyze" => self.analyze(AnalyzeKind::Leela, args),
+            "kata-analyze" => self.analyze(AnalyzeKind::Kata, args),
+            "time_settings" => {
+                self.time_settings = TimeSettings {
+                    main_time: parse_arg(args, 0)?,
+                    byo_yomi_time: parse_arg(args, 1)?,
+                    byo_yomi_stones: parse_arg(args, 2)?,
+                };
+                self.black_time_left = None;
+                self.white_time_left = None;
+                Ok("".into())
+            },
+            "time_left" => {
+                let time_left = Some((parse_arg(args, 1)?, parse_arg(args, 2)?));
+
+                match parse_color_arg(args, 0)? {
+                    Color::Black => { self.black_time_left = time_left },
+                    Color::White => { self.white_time_left = time_left },
+                }
+                Ok("".into())
+            },
+            _ => Err("unknown command".into()),
+        }
+    }
+
+    fn clear_board(&mut self, width: usize, height: usize) {
+        let mut record = GameRecord::new(width, height);
+        record.set_komi(self.record.komi());
+
+        self.record = record;
+        self.goban = Goban::new(width, height);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.record.actions().is_empty()
+    }
+
+    /// Returns the color of the player whose turn it is to move.
+    fn to_move(&self) -> Color {
+        match self.record.moves().last() {
+            Some(mv) => mv.color().opposite(),
+            None if self.record.handicap() >= 2 => Color::White,
+            None => Color::Black,
+        }
+    }
+
+    /// Returns the state of the current game, including its ko history and
+    /// the number of consecutive passes.
+    fn state(&self) -> Result<GameState, String> {
+        GameState::from_record(&self.record).map_err(|err| err.to_string())
+    }
+
+    fn options(&self) -> ScoreOptions {
+        ScoreOptions::new().with_num_threads(self.num_threads)
+    }
+
+    /// Returns how long the given player `color` may think about their next
+    /// move, according to the time settings and the latest `time_left`.
+    fn time_limit(&self, color: Color) -> Option<Duration> {
+        let time_left = match color {
+            Color::Black => self.black_time_left,
+            Color::White => self.white_time_left,
+        };
+
+        self.time_settings.time_for_move(time_left, self.goban.width() * self.goban.height())
+    }
+
+    /// Returns the search that is kept between moves if it is a search of the
+    /// given state `state`, otherwise replaces it with a new search.
+    fn search_of(&mut self, state: GameState) -> &mut Search {
+        let search = match self.search.take() {
+            Some(search) if is_same_state(search.state(), &state) => search,
+            _ => Search::new(state, self.options()),
+        };
+
+        self.search.insert(search)
+    }
+
+    fn parse_vertex_arg(&self, args: &[&str], i: usize) -> Result<Option<Point>, String> {
+        let text = args.get(i).ok_or("syntax error")?;
+
+        parse_vertex(text, self.goban.width(), self.goban.height()).ok_or_else(|| "syntax error".into())
+    }
+
+    fn boardsize(&mut self, args: &[&str]) -> Result<String, String> {
+        let size = parse_arg::<usize>(args, 0)?;
+
+        if (2..=25).contains(&size) {
+            self.clear_board(size, size);
+            Ok("".into())
+        } else {
+            Err("unacceptable size".into())
+        }
+    }
+
+    fn play_move(&mut self, mv: Move) -> Result<(), String> {
+        if let Some(at) = mv.at() {
+            self.goban.check_legal(at, mv.color()).map_err(|_| "illegal move")?;
+            self.goban.play(at, mv.color());
+        }
+
+        self.record.push_move(mv);
+
+        // advance the search that is kept between moves, or drop it if it
+        // does not follow the game
+        if let Some(search) = &mut self.search {
+            if search.state().to_move() != mv.color() || search.play(mv.at()).is_err() {
+                self.search = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn play(&mut self, args: &[&str]) -> Result<String, String> {
+        let color = parse_color_arg(args, 0)?;
+        let at = self.parse_vertex_arg(args, 1)?;
+
+        self.play_move(match at {
+            Some(at) => Move::play(color, at),
+            None => Move::pass(color),
+        })?;
+
+        Ok("".into())
+    }
+
+    fn genmove(&mut self, args: &[&str]) -> Result<String, String> {
+        let color = parse_color_arg(args, 0)?;
+        let state = self.state()?.with_to_move(color);
+        let time_limit = self.time_limit(color);
+        let search = self.search_of(state);
+        let at = match time_limit {
+            Some(time_limit) => search.run_with_time_limit(time_limit),
+            None => search.run(),
+        }.best_move();
+        let mv = match at {
+            Some(at) if self.goban.is_legal(at, color) => Move::play(color, at),
+            _ => Move::pass(color),
+        };
+
+        self.play_move(mv)?;
+        Ok(format_vertex(mv.at(), self.goban.height()))
+    }
+
+    fn undo(&mut self) -> Result<String, String> {
+        self.record.pop_move().ok_or("cannot undo")?;
+        self.goban = self.record.replay().play_to_end().map_err(|err| err.to_string())?;
+
+        Ok("".into())
+    }
+
+    fn fixed_handicap(&mut self, args: &[&str]) -> Result<String, String> {
+        let count = parse_arg::<usize>(args, 0)?;
+
+        if !self.is_empty() {
+            return Err("board not empty".into());
+        }
+
+        let points = fixed_handicap(self.goban.width(), self.goban.height(), count)
+            .ok_or("invalid number of stones")?;
+
+        self.place_handicap(&points)?;
+        Ok(points.iter().map(|&at| format_vertex(Some(at), self.goban.height())).collect::<Vec<_>>().join(" "))
+    }
+
+    fn set_free_handicap(&mut self, args: &[&str]) -> Result<String, String> {
+        if !self.is_empty() {
+            return Err("board not empty".into());
+        }
+
+        let points = (0..args.len())
+            .map(|i| self.parse_vertex_arg(args, i)?.ok_or_else(|| "syntax error".into()))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if points.len() < 2 || points.len() >= self.goban.width() * self.goban.height() {
+            return Err("bad vertex list".into());
+        }
+
+        self.place_handicap(&points)?;
+        Ok("".into())
+    }
+
+    fn place_handicap(&mut self, points: &[Point]) -> Result<(), String> {
+        for &at in points {
+            if !self.goban.is_legal(at, Color::Black) {
+                self.clear_board(self.goban.width(), self.goban.height());
+                return Err("bad vertex list".into());
+            }
+
+            self.goban.play(at, Color::Black);
+            self.record.push_setup(Color::Black, at);
+        }
+
+        self.record.set_handicap(points.len());
+        Ok(())
+    }
+
+    fn final_score(&self) -> Result<String, String> {
+        let state = self.state()?;
+        let score = Score::from_state(&state, self.options());
+
+        Ok(match state.rules() {
+            Rules::Area => score.final_area().result(),
+            Rules::Territory => score.final_territory().result(),
+        })
+    }
+
+    fn final_status_list(&self, args: &[&str]) -> Result<String, String> {
+        let status = args.first().ok_or("syntax error")?;
+        if !["alive", "dead", "seki"].contains(status) {
+            return Err("syntax error".into());
+        }
+
+        let state = self.state()?;
+        let score = Score::from_state(&state, self.options());
+        let dead_stones = score.dead_stones();
+        let seki = score.seki();
+        let points = match *status {
+            "alive" => {
+                self.goban.iter()
+                    .filter(|&at| self.goban.at(at).is_some() && !dead_stones.contains(&at) && !seki.contains(at))
+                    .collect()
+            },
+            "dead" => dead_stones,
+            _ => seki.stones().to_vec(),
+        };
+
+        Ok(points.into_iter().map(|at| format_vertex(Some(at), self.goban.height())).collect::<Vec<_>>().join(" "))
+    }
+
+    fn analyze(&mut self, kind: AnalyzeKind, args: &[&str]) -> Result<String, String> {
+        self.analyze = Some(Analyze::parse(kind, self.to_move(), args)?);
+
+        Ok("".into())
+    }
+
+    fn loadsgf(&mut self, args: &[&str]) -> Result<String, String> {
+        let path = args.first().ok_or("syntax error")?;
+        let text = fs::read(path).map_err(|_| "cannot load file")?;
+        let trees = sgf::parse(&String::from_utf8_lossy(&text)).map_err(|_| "cannot load file")?;
+        let mut record = GameRecord::from_sgf(&trees[0]).map_err(|_| "cannot load file")?;
+
+        if args.len() > 1 {
+            record.truncate(parse_arg::<usize>(args, 1)?);
+        }
+
+        self.goban = record.replay().play_to_end().map_err(|_| "cannot load file")?;
+        self.record = record;
+
+        Ok(format_color(self.to_move()).into())
+    }
+}
+
+/// Returns whether the kept search state `kept` is the same as the state of
+/// the game `state`, so that the kept search can be continued.
+///
+/// # Arguments
+///
+/// * `kept` -
+/// * `state` -
+///
+fn is_same_state(kept: &GameState, state: &GameState) -> bool {
+    kept.to_move() == state.to_move()
+        && kept.pass_count() == state.pass_count()
+        && kept.komi() == state.komi()
+        && kept.rules() == state.rules()
+        && kept.goban() == state.goban()
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[&str], i: usize) -> Result<T, String> {
+    args.get(i)
+        .and_then(|arg| arg.parse::<T>().ok())
+        .ok_or_else(|| "syntax error".into())
+}
+
+fn parse_color_arg(args: &[&str], i: usize) -> Result<Color, String> {
+    args.get(i)
+        .and_then(|arg| parse_color(arg))
+        .ok_or_else(|| "syntax error".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtp::client::pipe;
+    use std::io::{BufReader, Cursor};
+
+    fn run(commands: &str) -> String {
+        let mut output = vec! [];
+        Engine::new().run(Cursor::new(commands.to_string()), &mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn run_responds_to_every_command() {
+        assert_eq!(
+            run("1 protocol_version\n\n# comment\nname\nfoo\n2 known_command play\nquit\nname\n"),
+            "=1 2\n\n= gobit\n\n? unknown command\n\n=2 true\n\n= \n\n"
+        );
+    }
+
+    #[test]
+    fn play_rejects_illegal_moves() {
+        assert_eq!(
+            run("boardsize 9\nplay b e5\nplay w e5\nplay w j10\nplay w pass\nundo\nundo\nundo\n"),
+            "= \n\n= \n\n? illegal move\n\n? syntax error\n\n= \n\n= \n\n= \n\n? cannot undo\n\n"
+        );
+    }
+
+    #[test]
+    fn fixed_handicap_places_stones() {
+        let mut engine = Engine::new();
+
+        assert_eq!(engine.execute("fixed_handicap 3").unwrap().0, "= D4 Q16 D16\n\n");
+        assert_eq!(engine.execute("fixed_handicap 3").unwrap().0, "? board not empty\n\n");
+        assert_eq!(engine.goban().iter().filter(|&at| engine.goban().at(at) == Some(Color::Black)).count(), 3);
+        assert_eq!(engine.record().handicap(), 3);
+        assert_eq!(engine.to_move(), Color::White);
+    }
+
+    #[test]
+    fn set_free_handicap_places_stones() {
+        let mut engine = Engine::new();
+
+        assert_eq!(engine.execute("set_free_handicap A1 B2 C3").unwrap().0, "= \n\n");
+        assert_eq!(engine.execute("play w pass").unwrap().0, "= \n\n");
+        assert_eq!(engine.execute("undo").unwrap().0, "= \n\n");
+        assert_eq!(engine.goban().iter().filter(|&at| engine.goban().at(at) == Some(Color::Black)).count(), 3);
+    }
+
+    #[test]
+    fn genmove_plays_legal_move() {
+        let mut engine = Engine::new();
+
+        assert_eq!(engine.execute("boardsize 3").unwrap().0, "= \n\n");
+
+        let response = engine.execute("genmove b").unwrap().0;
+        let vertex = response.trim_start_matches("= ").trim_end();
+
+        assert!(vertex == "pass" || parse_vertex(vertex, 3, 3).is_some(), "{}", response);
+        assert_eq!(engine.record().moves().count(), 1);
+    }
+
+    #[test]
+    fn genmove_keeps_search_between_moves() {
+        let mut engine = Engine::new().with_num_threads(1);
+
+        assert_eq!(engine.execute("boardsize 3").unwrap().0, "= \n\n");
+        engine.execute("genmove b").unwrap();
+        assert!(engine.search.is_some());
+
+        let kept = engine.search.as_ref().unwrap().total_sims();
+        engine.execute("play w pass").unwrap();
+        assert!(engine.search.as_ref().is_some_and(|search| search.total_sims() <= kept));
+
+        engine.execute("komi 0.5").unwrap();
+        engine.execute("genmove b").unwrap();
+        assert_eq!(engine.search.as_ref().unwrap().state().komi(), 0.5);
+    }
+
+    #[test]
+    fn time_for_move_follows_time_settings() {
+        let settings = |main_time, byo_yomi_time, byo_yomi_stones| TimeSettings { main_time, byo_yomi_time, byo_yomi_stones };
+
+        assert_eq!(TimeSettings::default().time_for_move(None, 81), None);
+        assert_eq!(settings(0, 10, 0).time_for_move(None, 81), None);
+        assert_eq!(settings(100, 0, 0).time_for_move(None, 81), Some(Duration::from_secs_f32(0.9 * (100.0 / 27.0))));
+        assert_eq!(settings(100, 0, 0).time_for_move(Some((27, 0)), 81), Some(Duration::from_secs_f32(0.9)));
+        assert_eq!(settings(0, 30, 5).time_for_move(None, 81), Some(Duration::from_secs_f32(0.9 * 6.0)));
+        assert_eq!(settings(60, 30, 5).time_for_move(Some((20, 2)), 81), Some(Duration::from_secs_f32(0.9 * 10.0)));
+    }
+
+    #[test]
+    fn time_left_limits_genmove() {
+        let mut engine = Engine::new();
+
+        assert_eq!(engine.execute("time_settings 600 0 0").unwrap().0, "= \n\n");
+        assert_eq!(engine.time_limit(Color::White), Some(Duration::from_secs_f32(0.9 * (600.0 / 120.0))));
+        assert_eq!(engine.execute("time_left w 12 0").unwrap().0, "= \n\n");
+        assert_eq!(engine.time_limit(Color::White), Some(Duration::from_secs_f32(0.9 * (12.0 / 120.0))));
+        assert_eq!(engine.time_limit(Color::Black), Some(Duration::from_secs_f32(0.9 * (600.0 / 120.0))));
+        assert!(engine.execute("time_left x 12 0").unwrap().0.starts_with("? "));
+    }
+
+    #[test]
+    fn final_score_reports_winner() {
+        let mut engine = Engine::new();
+        engine.execute("boardsize 3");
+        engine.execute("komi 0.5");
+
+        for vertex in ["A3", "A1", "B3", "B1", "C2", "C1"] {
+            engine.execute(&format!("play b {}", vertex));
+        }
+        engine.execute("play w A2");
+
+        assert_eq!(engine.execute("final_score").unwrap().0, "= B+8.5\n\n");
+        assert_eq!(engine.execute("final_status_list dead").unwrap().0, "= A2\n\n");
+        assert_eq!(engine.execute("final_status_list alive").unwrap().0, "= A3 B3 C2 A1 B1 C1\n\n");
+    }
+
+    #[test]
+    fn lz_analyze_streams_until_next_command() {
+        let (reader, mut writer) = pipe();
+        let commands = thread::spawn(move || {
+            writer.write_all(b"boardsize 5\n1 lz-analyze b 1\n").unwrap();
+            thread::sleep(Duration::from_millis(200));
+            writer.write_all(b"2 name\n").unwrap();
+        });
+
+        let mut output = vec! [];
+        let mut engine = Engine::new();
+        engine.run(BufReader::new(reader), &mut output).unwrap();
+        commands.join().unwrap();
+
+        // the analysis is kept, so that a following genmove can continue it
+        assert!(engine.search.as_ref().is_some_and(|search| search.total_sims() > 0));
+
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+
+        assert_eq!(lines.next(), Some("= "));
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(lines.next(), Some("=1"));
+
+        let info = lines.next().unwrap();
+        assert!(info.starts_with("info move "), "{}", info);
+        assert!(info.contains(" visits ") && info.contains(" winrate ") && info.contains(" pv "), "{}", info);
+        assert!(output.ends_with("\n\n=2 gobit\n\n"), "{}", output);
+    }
+}
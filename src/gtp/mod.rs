@@ -0,0 +1,113 @@
+mod analyze;
+mod client;
+mod elo;
+mod engine;
+mod referee;
+
+use crate::{Color, Point};
+
+pub use self::analyze::*;
+pub use self::client::*;
+pub use self::elo::*;
+pub use self::engine::*;
+pub use self::referee::*;
+
+/// The letters used for the columns of a vertex, which skips the letter `I`.
+const COLUMNS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// Returns the color of the given GTP color `text`, e.g. `b` or `white`.
+///
+/// # Arguments
+///
+/// * `text` -
+///
+pub fn parse_color(text: &str) -> Option<Color> {
+    match text.to_ascii_lowercase().as_str() {
+        "b" | "black" => Some(Color::Black),
+        "w" | "white" => Some(Color::White),
+        _ => None,
+    }
+}
+
+/// Returns the GTP name of the given color `color`.
+///
+/// # Arguments
+///
+/// * `color` -
+///
+pub fn format_color(color: Color) -> &'static str {
+    match color {
+        Color::Black => "B",
+        Color::White => "W",
+    }
+}
+
+/// Returns the point of the given GTP vertex `text`, e.g. `D4`, on a board of
+/// the given size. Returns `Some(None)` for a pass, and `None` if the vertex is
+/// not valid or outside of the board.
+///
+/// # Arguments
+///
+/// * `text` -
+/// * `width` -
+/// * `height` -
+///
+pub fn parse_vertex(text: &str, width: usize, height: usize) -> Option<Option<Point>> {
+    let text = text.to_ascii_uppercase();
+
+    if text == "PASS" {
+        return Some(None);
+    }
+
+    let column = *text.as_bytes().first()?;
+    let x = COLUMNS.iter().position(|&other| other == column)?;
+    let row = text[1..].parse::<usize>().ok()?;
+
+    if x < width && row >= 1 && row <= height {
+        Some(Some(Point::from((x, height - row))))
+    } else {
+        None
+    }
+}
+
+/// Returns the GTP vertex of the given point `at`, or `pass` if it is `None`,
+/// on a board of the given height `height`.
+///
+/// # Arguments
+///
+/// * `at` -
+/// * `height` -
+///
+pub fn format_vertex(at: Option<Point>, height: usize) -> String {
+    match at {
+        None => "pass".into(),
+        Some(at) => {
+            let (x, y): (usize, usize) = at.into();
+
+            format!("{}{}", COLUMNS[x] as char, height - y)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_vertex_skips_i() {
+        assert_eq!(parse_vertex("A1", 19, 19), Some(Some((0usize, 18usize).into())));
+        assert_eq!(parse_vertex("j10", 19, 19), Some(Some((8usize, 9usize).into())));
+        assert_eq!(parse_vertex("T19", 19, 19), Some(Some((18usize, 0usize).into())));
+        assert_eq!(parse_vertex("pass", 19, 19), Some(None));
+        assert_eq!(parse_vertex("I5", 19, 19), None);
+        assert_eq!(parse_vertex("K10", 9, 9), None);
+        assert_eq!(parse_vertex("A0", 9, 9), None);
+    }
+
+    #[test]
+    fn format_vertex_is_inverse_of_parse_vertex() {
+        for text in ["A1", "J10", "T19", "pass"] {
+            assert_eq!(format_vertex(parse_vertex(text, 19, 19).unwrap(), 19), text);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{Color, record::GameRecord, score::{GameState, Score, ScoreOptions}};
+use super::{format_color, Client, ClientError};
+use std::io::{BufRead, Write};
+
+/// The outcome of a single game played between two engines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    winner: Option<Color>,
+    is_void: bool,
+    record: GameRecord,
+}
+
+impl Outcome {
+    /// Returns the color of the winner, or `None` if the game is a draw or
+    /// has no result.
+    pub fn winner(&self) -> Option<Color> {
+        self.winner
+    }
+
+    /// Returns true if the game has no result, because the engines did not
+    /// agree on whether a move was legal.
+    pub fn is_void(&self) -> bool {
+        self.is_void
+    }
+
+    /// Returns the record of the game, with the names of both engines and
+    /// the result filled in.
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+}
+
+/// Play a single game between the engines `black` and `white` on an empty
+/// board of the given size `size`, using the mirrored position of `black`
+/// as the referee. The game ends when either engine resigns, makes an
+/// illegal move (which forfeits the game), or after two consecutive passes
+/// or `max_moves` moves, in which case it is scored by area after removing
+/// the dead stones found using `Score`. If the opponent rejects a move that
+/// the player was allowed to make, e.g. because the engines use different
+/// ko rules, then the game has no result.
+///
+/// # Arguments
+///
+/// * `black` -
+/// * `white` -
+/// * `size` -
+/// * `komi` -
+/// * `max_moves` -
+///
+pub fn play_game<R: BufRead, W: Write>(
+    black: &mut Client<R, W>,
+    white: &mut Client<R, W>,
+    size: usize,
+    komi: f32,
+    max_moves: usize,
+) -> Result<Outcome, ClientError>
+{
+    for client in [&mut *black, &mut *white] {
+        client.boardsize(size)?;
+        client.clear_board()?;
+        client.komi(komi)?;
+    }
+
+    let black_name = black.name()?;
+    let white_name = white.name()?;
+    let mut to_move = Color::Black;
+    let mut pass_count = 0;
+    let mut num_moves = 0;
+    let result = loop {
+        if pass_count >= 2 || num_moves >= max_moves {
+            break None;
+        }
+
+        let (player, opponent) = match to_move {
+            Color::Black => (&mut *black, &mut *white),
+            Color::White => (&mut *white, &mut *black),
+        };
+        let winner = to_move.opposite();
+        let mv = match player.genmove(to_move) {
+            Ok(Some(mv)) => mv,
+            Ok(None) => { break Some((Some(winner), format!("{}+R", format_color(winner)))) },
+            Err(ClientError::Io(err)) => { return Err(ClientError::Io(err)) },
+            Err(_) => { break Some((Some(winner), format!("{}+F", format_color(winner)))) },
+        };
+
+        match opponent.play(mv) {
+            Ok(()) => {},
+            Err(ClientError::Io(err)) => { return Err(ClientError::Io(err)) },
+            Err(_) => { break Some((None, "Void".to_string())) },
+        }
+        pass_count = if mv.is_pass() { pass_count + 1 } else { 0 };
+        num_moves += 1;
+        to_move = to_move.opposite();
+    };
+
+    let is_void = matches!(result, Some((None, _)));
+    let (winner, result) = match result {
+        Some(result) => result,
+        None => {
+            let state = GameState::from_record(black.record())
+                .map_err(|err| ClientError::Protocol(err.to_string()))?
+                .with_komi(komi);
+            let area = Score::from_state(&state, ScoreOptions::default()).final_area();
+
+            (area.winner(), area.result())
+        }
+    };
+
+    let mut record = black.record().clone();
+    record.set_black_name(black_name);
+    record.set_white_name(white_name);
+    record.set_result(result);
+
+    Ok(Outcome { winner, is_void, record })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gtp::client::stand_in;
+    use std::io::Cursor;
+
+    #[test]
+    fn play_game_records_result() {
+        let mut black = stand_in();
+        let mut white = stand_in();
+        let outcome = play_game(&mut black, &mut white, 3, 0.5, 6).unwrap();
+        let record = outcome.record();
+
+        assert!(record.moves().count() <= 6);
+        assert_eq!(record.komi(), 0.5);
+        assert_eq!(record.black_name(), Some("gobit"));
+        assert_eq!(record.white_name(), Some("gobit"));
+        assert!(outcome.winner().is_some());
+        assert!(record.result().unwrap().starts_with(format_color(outcome.winner().unwrap())));
+        assert!(record.replay().play_to_end().unwrap() == *black.goban());
+        assert!(white.goban() == black.goban());
+    }
+
+    #[test]
+    fn rejected_move_voids_game() {
+        let mut black = Client::new(Cursor::new("= \n\n= \n\n= \n\n= black\n\n= C3\n\n"), vec! []);
+        let mut white = Client::new(Cursor::new("= \n\n= \n\n= \n\n= white\n\n? illegal move\n\n"), vec! []);
+        let outcome = play_game(&mut black, &mut white, 3, 0.5, 6).unwrap();
+
+        assert!(outcome.is_void());
+        assert_eq!(outcome.winner(), None);
+        assert_eq!(outcome.record().result(), Some("Void"));
+    }
+}
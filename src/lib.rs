@@ -3,13 +3,17 @@ mod block;
 mod color;
 mod display;
 mod goban;
+pub mod gtp;
 pub mod pattern;
 mod point;
+pub mod record;
 mod ring;
 pub mod score;
+pub mod sgf;
+pub mod svg;
 mod vertex;
 mod zobrist;
 
 pub use self::color::Color;
-pub use self::goban::Goban;
+pub use self::goban::{Goban, Illegal};
 pub use self::point::Point;
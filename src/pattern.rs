@@ -37,6 +37,70 @@ impl<'a, S: Searcher<'a>> Iterator for SearcherIter<'a, S> {
     }
 }
 
+/// Returns the color of the eye described by the given `3x3` pattern, or
+/// `None` if the pattern is not an eye. See `EyeSearcher` for the layout of
+/// the pattern.
+///
+/// # Arguments
+///
+/// * `pattern` -
+///
+fn eye_color(pattern: u16) -> Option<Color> {
+    match pattern {
+        // x x x
+        // x   x
+        // x x x
+        0xffff => Some(Color::Black),
+
+        //   x x
+        // x   x
+        // x x x
+        0x3fff | 0xf3ff | 0xffcf | 0xfffc => Some(Color::Black),
+
+        // o x x
+        // x   x
+        // x x x
+        0xbfff | 0xfbff | 0xfffe | 0xffef => Some(Color::Black),
+
+        // - - -
+        // x   x
+        // x x x
+        0xf77d | 0xffd5 | 0x7ddf | 0x57ff => Some(Color::Black),
+
+        // - - -
+        // -   x
+        // - x x
+        0xf755 | 0x7dd5 | 0x55df | 0x577d => Some(Color::Black),
+
+        // o o o
+        // o   o
+        // o o o
+        0xaaaa => Some(Color::White),
+
+        //   o o
+        // o   o
+        // o o o
+        0x2aaa | 0xa2aa | 0xaa8a | 0xaaa8 => Some(Color::White),
+
+        // x o o
+        // o   o
+        // o o o
+        0xeaaa | 0xaeaa | 0xaaa9 | 0xa6aa => Some(Color::White),
+
+        // - - -
+        // o   o
+        // o o o
+        0x699a | 0xaa96 | 0xa669 | 0x56aa => Some(Color::White),
+
+        // - - -
+        // -   o
+        // - o o
+        0x559a | 0x6995 | 0xa655 | 0x5669 => Some(Color::White),
+
+        _ => None
+    }
+}
+
 /// Traverse the board from the left to the right, and bottom to top checking
 /// the `3x3` pattern around each vertex whether it is an eye. To speed-up the
 /// performance of the pattern checking we store the pattern around each point
@@ -55,6 +119,7 @@ pub struct EyeSearcher<'a> {
 }
 
 impl<'a> Searcher<'a> for EyeSearcher<'a> {
+    #[allow(clippy::identity_op)]
     fn next(&mut self) -> SearchStep {
         let (x, y) = self.finger;
         let (width, height) = (self.goban.width() as u8, self.goban.height() as u8);
@@ -84,58 +149,9 @@ impl<'a> Searcher<'a> for EyeSearcher<'a> {
                 (x + 1, y)
             };
 
-            match self.pattern {
-                // x x x
-                // x   x
-                // x x x
-                0xffff => SearchStep::Match(Color::Black, (x, y).into()),
-
-                //   x x
-                // x   x
-                // x x x
-                0x3fff | 0xf3ff | 0xffcf | 0xfffc => SearchStep::Match(Color::Black, (x, y).into()),
-
-                // o x x
-                // x   x
-                // x x x
-                0xbfff | 0xfbff | 0xfffe | 0xffef => SearchStep::Match(Color::Black, (x, y).into()),
-
-                // - - -
-                // x   x
-                // x x x
-                0xf77d | 0xffd5 | 0x7ddf | 0x57ff => SearchStep::Match(Color::Black, (x, y).into()),
-
-                // - - -
-                // -   x
-                // - x x
-                0xf755 | 0x7dd5 | 0x55df | 0x577d => SearchStep::Match(Color::Black, (x, y).into()),
-
-                // o o o
-                // o   o
-                // o o o
-                0xaaaa => SearchStep::Match(Color::White, (x, y).into()),
-
-                //   o o
-                // o   o
-                // o o o
-                0x2aaa | 0xa2aa | 0xaa8a | 0xaaa8 => SearchStep::Match(Color::White, (x, y).into()),
-
-                // x o o
-                // o   o
-                // o o o
-                0xeaaa | 0xaeaa | 0xaaa9 | 0xa6aa => SearchStep::Match(Color::White, (x, y).into()),
-
-                // - - -
-                // o   o
-                // o o o
-                0x699a | 0xaa96 | 0xa669 | 0x56aa => SearchStep::Match(Color::White, (x, y).into()),
-
-                // - - -
-                // -   o
-                // - o o
-                0x559a | 0x6995 | 0xa655 | 0x5669 => SearchStep::Match(Color::White, (x, y).into()),
-
-                _ => SearchStep::Reject((x, y).into())
+            match eye_color(self.pattern) {
+                Some(color) => SearchStep::Match(color, (x, y).into()),
+                None => SearchStep::Reject((x, y).into()),
             }
         }
     }
@@ -148,12 +164,29 @@ impl<'a> IntoIterator for EyeSearcher<'a> {
     fn into_iter(self) -> Self::IntoIter {
         SearcherIter {
             searcher: self,
-            phantom: PhantomData::default(),
+            phantom: PhantomData,
         }
     }
 }
 
 impl<'a> EyeSearcher<'a> {
+    /// Returns the color of the eye at the given point `at`, or `None` if the
+    /// point is not an eye. This does not advance the searcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn eye_at(&self, at: Point) -> Option<Color> {
+        let (x, y): (u8, u8) = at.into();
+
+        if self.goban.at(at).is_some() {
+            None
+        } else {
+            eye_color(self.pattern_at(x, y))
+        }
+    }
+
     #[inline]
     fn bit_at(&self, x: u8, y: u8) -> u16 {
         if x >= self.goban.width() as u8 || y >= self.goban.height() as u8 {
@@ -168,6 +201,7 @@ impl<'a> EyeSearcher<'a> {
     }
 
     #[inline]
+    #[allow(clippy::identity_op)]
     fn pattern_at(&self, x: u8, y: u8) -> u16 {
         (self.bit_at(x.wrapping_sub(1), y.wrapping_sub(1)) << 14)
         | (self.bit_at(x.wrapping_sub(1), y+0) << 12)
@@ -182,6 +216,12 @@ impl<'a> EyeSearcher<'a> {
 
 pub struct Eye;
 
+impl Default for Eye {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Eye {
     pub fn new() -> Self {
         Self { }
@@ -200,6 +240,87 @@ impl<'a> Pattern<'a> for Eye {
     }
 }
 
+/// The `3x3` patterns of MoGo, centered on the move, that are good responses
+/// to a nearby move. `X` and `O` are stones of different colors, `x` and `o`
+/// mean anything except `X` or `O` respectively, `.` is empty, `#` is outside
+/// of the board, and `?` is anything.
+const RESPONSES: [&[u8; 9]; 14] = [
+    // hane
+    b"XOX...???",
+    b"XO....?.?",
+    b"XO?X..x.?",
+    b"XOO...?.?",
+
+    // katatsuke, or diagonal attachment
+    b".O.X.....",
+
+    // cut
+    b"XO?O.o?o?",
+    b"XO?O.X???",
+    b"?X?O.Oooo",
+    b"OX?o.O???",
+
+    // edge
+    b"X.?O.?###",
+    b"OX?X.O###",
+    b"?X?x.O###",
+    b"?XOx.x###",
+    b"?OXX.O###",
+];
+
+/// Returns whether the given pattern `pattern` matches the neighbourhood of
+/// `at`, where the offsets are rotated or reflected using the `2x2` matrix
+/// `transform`, and where `X` is of the given color `x`.
+fn is_match(goban: &Goban, at: Point, pattern: &[u8; 9], transform: [i8; 4], x: Color) -> bool {
+    (0..9).all(|i| {
+        let (x0, y0) = ((i % 3) as i8 - 1, (i / 3) as i8 - 1);
+        let (dx, dy) = (transform[0] * x0 + transform[1] * y0, transform[2] * x0 + transform[3] * y0);
+        let other = Point::new(
+            (at.x() as u8).wrapping_add_signed(dx),
+            (at.y() as u8).wrapping_add_signed(dy),
+        );
+        let is_valid = goban[other].is_valid();
+        let color = if is_valid { goban.at(other) } else { None };
+
+        match pattern[i] {
+            b'X' => color == Some(x),
+            b'O' => color == Some(x.opposite()),
+            b'x' => color != Some(x),
+            b'o' => color != Some(x.opposite()),
+            b'.' => is_valid && color.is_none(),
+            b'#' => !is_valid,
+            _ => true,
+        }
+    })
+}
+
+/// Returns whether the empty point `at` matches one of the `3x3` MoGo
+/// response patterns, in any rotation, reflection, or color.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `at` -
+///
+pub fn is_response(goban: &Goban, at: Point) -> bool {
+    const TRANSFORMS: [[i8; 4]; 8] = [
+        [1, 0, 0, 1],
+        [-1, 0, 0, 1],
+        [1, 0, 0, -1],
+        [-1, 0, 0, -1],
+        [0, 1, 1, 0],
+        [0, -1, 1, 0],
+        [0, 1, -1, 0],
+        [0, -1, -1, 0],
+    ];
+
+    goban.at(at).is_none() && RESPONSES.iter().any(|pattern| {
+        TRANSFORMS.iter().any(|&transform| {
+            is_match(goban, at, pattern, transform, Color::Black) || is_match(goban, at, pattern, transform, Color::White)
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,4 +406,41 @@ mod tests {
             ]
         );
     }
-}
\ No newline at end of file
+
+    /// ```
+    /// . x .
+    /// x x .
+    /// . . .
+    /// ```
+    #[test]
+    fn eye_at_checks_single_point() {
+        let mut goban = Goban::new(3, 3);
+        for at in [(1u8, 0u8), (0, 1), (1, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+
+        let eyes = Eye::new().into_searcher(&goban);
+
+        assert_eq!(eyes.eye_at((0u8, 0u8).into()), Some(Color::Black));
+        assert_eq!(eyes.eye_at((2u8, 0u8).into()), None);
+        assert_eq!(eyes.eye_at((1u8, 1u8).into()), None);
+    }
+
+    /// ```
+    /// . . . . .
+    /// . x o . .
+    /// . . . . .
+    /// . . . . .
+    /// ```
+    #[test]
+    fn is_response_detects_hane() {
+        let mut goban = Goban::new(5, 4);
+        goban.play((1u8, 1u8).into(), Color::Black);
+        goban.play((2u8, 1u8).into(), Color::White);
+
+        assert!(is_response(&goban, (2u8, 2u8).into()));
+        assert!(is_response(&goban, (2u8, 0u8).into()));
+        assert!(!is_response(&goban, (4u8, 3u8).into()));
+        assert!(!is_response(&goban, (1u8, 1u8).into()));
+    }
+}
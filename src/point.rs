@@ -12,19 +12,19 @@ impl From<(usize, usize)> for Point {
 
 impl From<(u8, u8)> for Point {
     fn from(value: (u8, u8)) -> Self {
-        Self::new(value.0 as u8 + 1, value.1 as u8 + 1)
+        Self::new(value.0 + 1, value.1 + 1)
     }
 }
 
-impl Into<(usize, usize)> for Point {
-    fn into(self) -> (usize, usize) {
-        (self.x as usize - 1, self.y as usize - 1)
+impl From<Point> for (usize, usize) {
+    fn from(value: Point) -> Self {
+        (value.x as usize - 1, value.y as usize - 1)
     }
 }
 
-impl Into<(u8, u8)> for Point {
-    fn into(self) -> (u8, u8) {
-        (self.x - 1, self.y - 1)
+impl From<Point> for (u8, u8) {
+    fn from(value: Point) -> Self {
+        (value.x - 1, value.y - 1)
     }
 }
 
@@ -41,6 +41,15 @@ impl Point {
         (0..4).map(move |i| Self::new(x.wrapping_add(DELTA[i]), y.wrapping_add(DELTA[i+2])))
     }
 
+    /// Returns the eight points surrounding this point, i.e. the neighbours
+    /// and the diagonals.
+    pub fn surroundings(&self) -> impl Iterator<Item=Point> {
+        let (x, y) = (self.x, self.y);
+
+        [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)].into_iter()
+            .map(move |(dx, dy): (i8, i8)| Self::new(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy)))
+    }
+
     pub fn x(&self) -> usize {
         self.x as usize
     }
@@ -0,0 +1,160 @@
+use crate::{Color, Point};
+use super::{FormatError, GameRecord, Move};
+
+/// Returns the value of the given key `key` in the comma separated list of
+/// `KEY:value` pairs `info`.
+fn info_value<'a>(info: &'a str, key: &str) -> Option<&'a str> {
+    info.split(',')
+        .filter_map(|pair| pair.split_once(':'))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.trim())
+}
+
+/// Returns the result of the game from the `GRLT` result code and `ZIPSU`
+/// margin (in tenths of a point) of the game information.
+fn parse_result(grlt: &str, zipsu: Option<&str>) -> Option<String> {
+    let margin = || zipsu.and_then(|z| z.parse::<f32>().ok()).map(|z| z / 10.0);
+
+    match grlt {
+        "0" => margin().map(|m| format!("B+{}", m)),
+        "1" => margin().map(|m| format!("W+{}", m)),
+        "3" => Some("B+R".into()),
+        "4" => Some("W+R".into()),
+        "7" => Some("B+T".into()),
+        "8" => Some("W+T".into()),
+        _ => None,
+    }
+}
+
+/// Returns the value of a header line of the form `\[NAME=value\]`.
+fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    line.strip_prefix("\\[")?
+        .strip_prefix(name)?
+        .strip_prefix('=')?
+        .strip_suffix("\\]")
+        .map(|value| value.trim())
+}
+
+impl GameRecord {
+    /// Returns the record of the given Tygem `.gib` game `text`. Tygem games
+    /// are always played on a `19x19` board, and the handicap stones are
+    /// placed on the standard points.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` -
+    ///
+    pub fn from_gib(text: &str) -> Result<Self, FormatError> {
+        let mut record = GameRecord::new(19, 19);
+        let mut next_color = Color::Black;
+        let mut is_game = false;
+
+        for (i, line) in text.lines().map(|line| line.trim()).enumerate() {
+            let invalid_line = || FormatError::InvalidLine(i + 1, line.into());
+
+            if let Some(name) = header_value(line, "GAMEBLACKNAME") {
+                record.set_black_name(name);
+            } else if let Some(name) = header_value(line, "GAMEWHITENAME") {
+                record.set_white_name(name);
+            } else if let Some(info) = header_value(line, "GAMEINFOMAIN") {
+                if let Some(komi) = info_value(info, "GONGJE").and_then(|k| k.parse::<f32>().ok()) {
+                    record.set_komi(komi / 10.0);
+                }
+
+                if let Some(result) = info_value(info, "GRLT").and_then(|grlt| parse_result(grlt, info_value(info, "ZIPSU"))) {
+                    record.set_result(result);
+                }
+            } else if line.starts_with("INI ") {
+                let handicap = line.split_whitespace().nth(3)
+                    .and_then(|h| h.parse::<usize>().ok())
+                    .ok_or_else(invalid_line)?;
+
+                if handicap >= 2 {
+                    record.set_handicap(handicap);
+                    if !record.push_fixed_handicap() {
+                        return Err(invalid_line());
+                    }
+
+                    next_color = Color::White;
+                }
+
+                is_game = true;
+            } else if line.starts_with("STO ") {
+                let fields = line.split_whitespace()
+                    .skip(3)
+                    .map(|f| f.parse::<u8>().map_err(|_| invalid_line()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (color, x, y) = match fields[..] {
+                    [1, x, y] => (Color::Black, x, y),
+                    [2, x, y] => (Color::White, x, y),
+                    _ => { return Err(invalid_line()) }
+                };
+
+                if x >= 19 || y >= 19 {
+                    return Err(invalid_line());
+                }
+
+                record.push_move(Move::play(color, Point::from((x, y))));
+                next_color = color.opposite();
+                is_game = true;
+            } else if line.starts_with("SKI ") {
+                record.push_move(Move::pass(next_color));
+                next_color = next_color.opposite();
+                is_game = true;
+            }
+        }
+
+        if is_game {
+            Ok(record)
+        } else {
+            Err(FormatError::Missing("INI"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: &str = "\\HS\r
+\\[GAMEINFOMAIN=GBKIND:3,GTYPE:0,GCDT:0,GTIME:600-30-3,GRLT:1,ZIPSU:35,GONGJE:65,GAMEDATE:2020-1-1\\]\r
+\\[GAMEWHITENAME=White (9D)\\]\r
+\\[GAMEBLACKNAME=Black (8D)\\]\r
+\\HE\r
+\\GS\r
+2 1 0\r
+127 0 &4\r
+INI 0 1 2 &4\r
+STO 0 2 2 15 15\r
+SKI 0 3\r
+STO 0 4 2 3 3\r
+\\GE\r
+";
+
+    #[test]
+    fn from_gib_reads_game() {
+        let record = GameRecord::from_gib(GIB).unwrap();
+
+        assert_eq!(record.black_name(), Some("Black (8D)"));
+        assert_eq!(record.white_name(), Some("White (9D)"));
+        assert_eq!(record.result(), Some("W+3.5"));
+        assert_eq!(record.komi(), 6.5);
+        assert_eq!(record.handicap(), 2);
+        assert_eq!(record.actions().len(), 5);
+        assert_eq!(record.moves().collect::<Vec<_>>(), vec! [
+            Move::play(Color::White, (15u8, 15u8).into()),
+            Move::pass(Color::Black),
+            Move::play(Color::White, (3u8, 3u8).into()),
+        ]);
+        assert!(record.replay().play_to_end().is_ok());
+    }
+
+    #[test]
+    fn from_gib_rejects_invalid_moves() {
+        assert_eq!(
+            GameRecord::from_gib("INI 0 1 0 &4\nSTO 0 2 1 19 3\n"),
+            Err(FormatError::InvalidLine(2, "STO 0 2 1 19 3".into()))
+        );
+        assert_eq!(GameRecord::from_gib("\\HS\n\\HE\n"), Err(FormatError::Missing("INI")));
+    }
+}
@@ -0,0 +1,331 @@
+mod gib;
+mod ngf;
+mod replay;
+mod ugf;
+
+use crate::{Color, Point};
+use std::fmt::Display;
+
+pub use self::replay::*;
+
+/// The error returned when a game record in one of the non-SGF formats could
+/// not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The required field with the given name is missing.
+    Missing(&'static str),
+
+    /// The line with the given number (starting at `1`) could not be
+    /// understood.
+    InvalidLine(usize, String),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "missing field {}", name),
+            Self::InvalidLine(line_number, line) => write!(f, "invalid line {}: {}", line_number, line),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    // pass
+}
+
+/// Returns the standard placement of `count` fixed handicap stones on a board
+/// of the given size, as defined by the Go Text Protocol. Returns `None` if
+/// the board does not support the given number of handicap stones.
+///
+/// # Arguments
+///
+/// * `width` -
+/// * `height` -
+/// * `count` -
+///
+pub fn fixed_handicap(width: usize, height: usize, count: usize) -> Option<Vec<Point>> {
+    let has_center = width % 2 == 1 && height % 2 == 1 && width >= 9 && height >= 9;
+    let max_count = if has_center { 9 } else { 4 };
+
+    if width < 7 || height < 7 || count < 2 || count > max_count {
+        return None;
+    }
+
+    let edge = |size: usize| if size >= 13 { 3 } else { 2 };
+    let (x0, x1, xm) = (edge(width), width - 1 - edge(width), width / 2);
+    let (y0, y1, ym) = (edge(height), height - 1 - edge(height), height / 2);
+
+    let points = [
+        (x0, y1), (x1, y0), (x0, y0), (x1, y1),
+        (x0, ym), (x1, ym), (xm, y1), (xm, y0),
+    ];
+    let num_points = if count % 2 == 1 && count >= 5 { count - 1 } else { count };
+    let center = if num_points < count { Some((xm, ym)) } else { None };
+
+    Some(
+        points.into_iter()
+            .take(num_points)
+            .chain(center)
+            .map(Point::from)
+            .collect()
+    )
+}
+
+/// A single move of a game, either a stone played at some point or a pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    color: Color,
+    at: Option<Point>,
+}
+
+impl Move {
+    pub fn play(color: Color, at: Point) -> Self {
+        Self { color, at: Some(at) }
+    }
+
+    pub fn pass(color: Color) -> Self {
+        Self { color, at: None }
+    }
+
+    /// Returns the color of the player that made this move.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Returns the point this move was played at, or `None` if it is a pass.
+    pub fn at(&self) -> Option<Point> {
+        self.at
+    }
+
+    pub fn is_pass(&self) -> bool {
+        self.at.is_none()
+    }
+}
+
+/// A single step of a game record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// A stone placed on the board outside of the normal flow of the game,
+    /// for example handicap stones.
+    Setup(Color, Point),
+
+    /// A point that is cleared of any stone outside of the normal flow of the
+    /// game.
+    Clear(Point),
+
+    /// A move made by one of the players.
+    Move(Move),
+}
+
+/// The game information and sequence of moves of a single game, independent
+/// of the file format it was read from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    width: usize,
+    height: usize,
+    komi: f32,
+    handicap: usize,
+    black_name: Option<String>,
+    white_name: Option<String>,
+    result: Option<String>,
+    rules: Option<String>,
+    actions: Vec<Action>,
+}
+
+impl GameRecord {
+    /// Returns an empty record of a game played on a board of the given width
+    /// `width` and height `height`.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` -
+    /// * `height` -
+    ///
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            komi: 0.0,
+            handicap: 0,
+            black_name: None,
+            white_name: None,
+            result: None,
+            rules: None,
+            actions: vec! [],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+
+    pub fn set_komi(&mut self, komi: f32) {
+        self.komi = komi;
+    }
+
+    /// Returns the number of handicap stones, as recorded in the game
+    /// information. The handicap stones themselves are stored as setup
+    /// actions.
+    pub fn handicap(&self) -> usize {
+        self.handicap
+    }
+
+    pub fn set_handicap(&mut self, handicap: usize) {
+        self.handicap = handicap;
+    }
+
+    pub fn black_name(&self) -> Option<&str> {
+        self.black_name.as_deref()
+    }
+
+    pub fn set_black_name(&mut self, black_name: impl Into<String>) {
+        self.black_name = Some(black_name.into());
+    }
+
+    pub fn white_name(&self) -> Option<&str> {
+        self.white_name.as_deref()
+    }
+
+    pub fn set_white_name(&mut self, white_name: impl Into<String>) {
+        self.white_name = Some(white_name.into());
+    }
+
+    /// Returns the result of the game, in the SGF `RE` notation, e.g. `B+R`
+    /// or `W+0.5`.
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+
+    pub fn set_result(&mut self, result: impl Into<String>) {
+        self.result = Some(result.into());
+    }
+
+    pub fn rules(&self) -> Option<&str> {
+        self.rules.as_deref()
+    }
+
+    pub fn set_rules(&mut self, rules: impl Into<String>) {
+        self.rules = Some(rules.into());
+    }
+
+    /// Returns all setup stones and moves of this game, in order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns all moves of this game, in order.
+    pub fn moves(&self) -> impl Iterator<Item=Move> + '_ {
+        self.actions.iter().filter_map(|action| match action {
+            Action::Move(mv) => Some(*mv),
+            Action::Setup(_, _) | Action::Clear(_) => None,
+        })
+    }
+
+    /// Place a setup stone of the given color `color` at `at`.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    /// * `at` -
+    ///
+    pub fn push_setup(&mut self, color: Color, at: Point) {
+        self.actions.push(Action::Setup(color, at));
+    }
+
+    /// Remove any stone at `at`, outside of the normal flow of the game.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn push_clear(&mut self, at: Point) {
+        self.actions.push(Action::Clear(at));
+    }
+
+    /// Append the given move `mv` to the end of the game.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` -
+    ///
+    pub fn push_move(&mut self, mv: Move) {
+        self.actions.push(Action::Move(mv));
+    }
+
+    /// Remove everything from the move with the given number `move_number`
+    /// and onward, where the first move of the game is `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `move_number` -
+    ///
+    pub fn truncate(&mut self, move_number: usize) {
+        let i = self.actions.iter()
+            .enumerate()
+            .filter(|(_, action)| matches!(action, Action::Move(_)))
+            .nth(move_number.saturating_sub(1))
+            .map(|(i, _)| i);
+
+        if let Some(i) = i {
+            self.actions.truncate(i);
+        }
+    }
+
+    /// Remove the last move of the game, and any setup stones that follow it.
+    /// Returns the removed move, or `None` if the game has no moves.
+    pub fn pop_move(&mut self) -> Option<Move> {
+        let i = self.actions.iter().rposition(|action| matches!(action, Action::Move(_)))?;
+
+        match self.actions.drain(i..).next() {
+            Some(Action::Move(mv)) => Some(mv),
+            _ => None,
+        }
+    }
+
+    /// Place the standard fixed handicap stones for the handicap of this game,
+    /// returns `false` if the board does not support that many stones.
+    fn push_fixed_handicap(&mut self) -> bool {
+        match fixed_handicap(self.width, self.height, self.handicap) {
+            Some(points) => {
+                for at in points {
+                    self.push_setup(Color::Black, at);
+                }
+
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Returns a replay of this game, that checks the legality of every move
+    /// as it is played.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_handicap_returns_standard_points() {
+        assert_eq!(fixed_handicap(19, 19, 1), None);
+        assert_eq!(fixed_handicap(19, 19, 10), None);
+        assert_eq!(fixed_handicap(10, 10, 5), None);
+        assert_eq!(
+            fixed_handicap(19, 19, 3),
+            Some(vec! [(3usize, 15usize).into(), (15usize, 3usize).into(), (3usize, 3usize).into()])
+        );
+        assert_eq!(fixed_handicap(19, 19, 5).unwrap()[4], (9usize, 9usize).into());
+        assert_eq!(fixed_handicap(9, 9, 8).unwrap()[7], (4usize, 2usize).into());
+    }
+}
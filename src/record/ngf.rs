@@ -0,0 +1,151 @@
+use crate::{Color, Point};
+use super::{FormatError, GameRecord, Move};
+
+/// Returns the result of the game from the free-form result line of the
+/// header, e.g. `White wins by resignation` or `Black wins by 3.5`.
+fn parse_result(line: &str) -> Option<String> {
+    let line = line.to_ascii_lowercase();
+    let winner = if line.contains("white win") {
+        'W'
+    } else if line.contains("black win") {
+        'B'
+    } else {
+        return None;
+    };
+
+    if line.contains("resign") {
+        Some(format!("{}+R", winner))
+    } else if line.contains("time") {
+        Some(format!("{}+T", winner))
+    } else {
+        let margin = line.split_whitespace()
+            .map(|word| word.trim_matches(|ch: char| !ch.is_ascii_digit()))
+            .find_map(|word| word.parse::<f32>().ok());
+
+        Some(match margin {
+            Some(margin) => format!("{}+{}", winner, margin),
+            None => format!("{}+", winner),
+        })
+    }
+}
+
+/// Returns the name of the player from a header line of the form
+/// `name rank`.
+fn parse_player(line: &str) -> Option<&str> {
+    line.split_whitespace().next()
+}
+
+impl GameRecord {
+    /// Returns the record of the given WBaduk `.ngf` game `text`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` -
+    ///
+    pub fn from_ngf(text: &str) -> Result<Self, FormatError> {
+        let lines = text.lines().map(|line| line.trim()).collect::<Vec<_>>();
+        let header = |i: usize, name: &'static str| lines.get(i).copied().ok_or(FormatError::Missing(name));
+        let invalid_line = |i: usize| FormatError::InvalidLine(i + 1, lines[i].into());
+
+        let size = header(1, "size")?.parse::<usize>().map_err(|_| invalid_line(1))?;
+        if !(2..=25).contains(&size) {
+            return Err(invalid_line(1));
+        }
+
+        let handicap = header(5, "handicap")?.parse::<usize>().map_err(|_| invalid_line(5))?;
+        let komi = header(7, "komi")?.parse::<f32>().map_err(|_| invalid_line(7))?;
+        let mut record = GameRecord::new(size, size);
+
+        record.set_komi(komi);
+        if let Some(name) = parse_player(header(2, "white")?) {
+            record.set_white_name(name);
+        }
+        if let Some(name) = parse_player(header(3, "black")?) {
+            record.set_black_name(name);
+        }
+        if let Some(result) = parse_result(header(10, "result")?) {
+            record.set_result(result);
+        }
+        if handicap >= 2 {
+            record.set_handicap(handicap);
+            if !record.push_fixed_handicap() {
+                return Err(invalid_line(5));
+            }
+        }
+
+        // every move is of the form `PM` followed by the move number, the
+        // color, and the coordinates where `B` is the first line
+        for (i, line) in lines.iter().enumerate().skip(11) {
+            if !line.starts_with("PM") {
+                continue;
+            }
+
+            let (color, x, y) = match line.as_bytes() {
+                [_, _, _, _, b'B', x, y, ..] => (Color::Black, *x, *y),
+                [_, _, _, _, b'W', x, y, ..] => (Color::White, *x, *y),
+                _ => { return Err(invalid_line(i)) }
+            };
+            let x = x.wrapping_sub(b'B') as usize;
+            let y = y.wrapping_sub(b'B') as usize;
+
+            record.push_move(if x < size && y < size {
+                Move::play(color, Point::from((x, y)))
+            } else {
+                Move::pass(color)
+            });
+        }
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NGF: &str = "Test game
+19
+White       5D*
+Black       4D*
+www.cyberoro.com
+0
+0
+6
+20200101 [12:00]
+5
+White wins by resignation!
+3
+PMABBQDQD
+PMACWDPDP
+PMADBAAAA
+";
+
+    #[test]
+    fn from_ngf_reads_game() {
+        let record = GameRecord::from_ngf(NGF).unwrap();
+
+        assert_eq!(record.black_name(), Some("Black"));
+        assert_eq!(record.white_name(), Some("White"));
+        assert_eq!(record.result(), Some("W+R"));
+        assert_eq!(record.komi(), 6.0);
+        assert_eq!(record.handicap(), 0);
+        assert_eq!(record.moves().collect::<Vec<_>>(), vec! [
+            Move::play(Color::Black, (15u8, 2u8).into()),
+            Move::play(Color::White, (2u8, 14u8).into()),
+            Move::pass(Color::Black),
+        ]);
+    }
+
+    #[test]
+    fn parse_result_reads_margin() {
+        assert_eq!(parse_result("Black wins by 2.5!"), Some("B+2.5".into()));
+        assert_eq!(parse_result("White wins by 7 points."), Some("W+7".into()));
+        assert_eq!(parse_result("White wins on time!"), Some("W+T".into()));
+        assert_eq!(parse_result("Black wins!"), Some("B+".into()));
+    }
+
+    #[test]
+    fn from_ngf_rejects_truncated_header() {
+        assert_eq!(GameRecord::from_ngf("Test game\n19\n"), Err(FormatError::Missing("handicap")));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{Color, Goban, Illegal, Point};
+use super::{Action, GameRecord, Move};
+use std::{fmt::Display, slice::Iter};
+
+/// The error returned when a game record violates the rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The setup stone of the given color at the given point, that precedes
+    /// the move with the given number, could not be placed. The color is
+    /// `None` if the point was to be cleared.
+    IllegalSetup(usize, Option<Color>, Point, Illegal),
+
+    /// The move with the given number is illegal.
+    IllegalMove(usize, Move, Illegal),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IllegalSetup(move_number, Some(color), at, reason) => {
+                write!(f, "illegal setup stone {:?} at {:?} before move {}: {}", color, at, move_number, reason)
+            },
+            Self::IllegalSetup(move_number, None, at, reason) => {
+                write!(f, "illegal cleared point {:?} before move {}: {}", at, move_number, reason)
+            },
+            Self::IllegalMove(move_number, mv, reason) => {
+                write!(f, "illegal move {} ({:?} at {:?}): {}", move_number, mv.color(), mv.at(), reason)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    // pass
+}
+
+/// Replays a game record onto a `Goban`, one move at a time. Every item of
+/// the iterator is the move that was just played, and the position after it
+/// can be inspected using `goban()`. Setup stones are placed, and points
+/// cleared, just before the move that follows them without checking them
+/// against the ko history or capturing anything.
+///
+/// Iteration stops after the first illegal move.
+pub struct Replay<'a> {
+    actions: Iter<'a, Action>,
+    goban: Goban,
+    move_number: usize,
+    pass_count: usize,
+    to_move: Color,
+    black_captures: usize,
+    white_captures: usize,
+    is_done: bool,
+}
+
+impl<'a> Iterator for Replay<'a> {
+    type Item = Result<Move, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        let result = self.step();
+        self.is_done = !matches!(result, Some(Ok(_)));
+
+        result
+    }
+}
+
+impl<'a> Replay<'a> {
+    pub(super) fn new(record: &'a GameRecord) -> Self {
+        let to_move = record.moves().next()
+            .map(|mv| mv.color())
+            .unwrap_or(if record.handicap() >= 2 { Color::White } else { Color::Black });
+
+        Self {
+            actions: record.actions().iter(),
+            goban: Goban::new(record.width(), record.height()),
+            move_number: 0,
+            pass_count: 0,
+            to_move,
+            black_captures: 0,
+            white_captures: 0,
+            is_done: false,
+        }
+    }
+
+    fn step(&mut self) -> Option<Result<Move, ReplayError>> {
+        for action in self.actions.by_ref() {
+            match *action {
+                Action::Setup(color, at) => {
+                    if let Err(reason) = self.goban.setup(at, Some(color)) {
+                        return Some(Err(ReplayError::IllegalSetup(self.move_number + 1, Some(color), at, reason)));
+                    }
+                },
+                Action::Clear(at) => {
+                    if let Err(reason) = self.goban.setup(at, None) {
+                        return Some(Err(ReplayError::IllegalSetup(self.move_number + 1, None, at, reason)));
+                    }
+                },
+                Action::Move(mv) => {
+                    self.move_number += 1;
+
+                    if let Some(at) = mv.at() {
+                        if let Err(reason) = self.goban.check_legal(at, mv.color()) {
+                            return Some(Err(ReplayError::IllegalMove(self.move_number, mv, reason)));
+                        }
+
+                        let captures = self.goban.play(at, mv.color());
+                        self.pass_count = 0;
+
+                        match mv.color() {
+                            Color::Black => { self.black_captures += captures },
+                            Color::White => { self.white_captures += captures },
+                        }
+                    } else {
+                        self.pass_count += 1;
+                    }
+
+                    self.to_move = mv.color().opposite();
+                    return Some(Ok(mv));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the current position.
+    pub fn goban(&self) -> &Goban {
+        &self.goban
+    }
+
+    /// Returns the number of the most recently played move, starting at `1`
+    /// for the first move of the game.
+    pub fn move_number(&self) -> usize {
+        self.move_number
+    }
+
+    /// Returns the number of consecutive passes that ended the sequence of
+    /// moves played so far.
+    pub fn pass_count(&self) -> usize {
+        self.pass_count
+    }
+
+    /// Returns the color of the player whose turn it is to move.
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    /// Returns the number of stones captured by the given color `color` so
+    /// far, i.e. the number of prisoners they hold.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    ///
+    pub fn captures(&self, color: Color) -> usize {
+        match color {
+            Color::Black => self.black_captures,
+            Color::White => self.white_captures,
+        }
+    }
+
+    /// Play all remaining moves of the game, and returns the final position.
+    pub fn play_to_end(mut self) -> Result<Goban, ReplayError> {
+        for result in self.by_ref() {
+            result?;
+        }
+
+        Ok(self.goban)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_yields_every_move() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_setup(Color::Black, (2u8, 2u8).into());
+        record.push_move(Move::play(Color::White, (4u8, 4u8).into()));
+        record.push_move(Move::pass(Color::Black));
+        record.push_move(Move::play(Color::White, (6u8, 6u8).into()));
+
+        let mut replay = record.replay();
+
+        assert_eq!(replay.next(), Some(Ok(Move::play(Color::White, (4u8, 4u8).into()))));
+        assert_eq!(replay.goban().at((2u8, 2u8).into()), Some(Color::Black));
+        assert_eq!(replay.goban().at((4u8, 4u8).into()), Some(Color::White));
+        assert_eq!(replay.next(), Some(Ok(Move::pass(Color::Black))));
+        assert_eq!(replay.pass_count(), 1);
+        assert_eq!(replay.to_move(), Color::White);
+        assert_eq!(replay.next(), Some(Ok(Move::play(Color::White, (6u8, 6u8).into()))));
+        assert_eq!(replay.move_number(), 3);
+        assert_eq!(replay.next(), None);
+    }
+
+    /// ```
+    /// . x o .
+    /// x o . o
+    /// . x o .
+    /// ```
+    #[test]
+    fn replay_counts_captures() {
+        let mut record = GameRecord::new(4, 3);
+        for (color, at) in [
+            (Color::Black, (1u8, 0u8)), (Color::White, (2u8, 0u8)),
+            (Color::Black, (0u8, 1u8)), (Color::White, (3u8, 1u8)),
+            (Color::Black, (1u8, 2u8)), (Color::White, (2u8, 2u8)),
+            (Color::Black, (2u8, 1u8)), (Color::White, (1u8, 1u8)),
+        ] {
+            record.push_move(Move::play(color, at.into()));
+        }
+
+        let mut replay = record.replay();
+        for result in replay.by_ref() {
+            result.unwrap();
+        }
+
+        assert_eq!(replay.captures(Color::White), 1);
+        assert_eq!(replay.captures(Color::Black), 0);
+        assert_eq!(replay.goban().at((2u8, 1u8).into()), None);
+    }
+
+    #[test]
+    fn replay_reports_illegal_move() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_move(Move::play(Color::Black, (4u8, 4u8).into()));
+        record.push_move(Move::play(Color::White, (4u8, 4u8).into()));
+        record.push_move(Move::play(Color::Black, (5u8, 5u8).into()));
+
+        let mut replay = record.replay();
+
+        assert!(matches!(replay.next(), Some(Ok(_))));
+        assert_eq!(
+            replay.next(),
+            Some(Err(ReplayError::IllegalMove(2, Move::play(Color::White, (4u8, 4u8).into()), Illegal::Occupied)))
+        );
+        assert_eq!(replay.next(), None);
+    }
+
+    #[test]
+    fn replay_reports_illegal_setup() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_move(Move::play(Color::Black, (4u8, 4u8).into()));
+        record.push_setup(Color::White, (4u8, 4u8).into());
+        record.push_move(Move::play(Color::White, (5u8, 5u8).into()));
+
+        assert_eq!(
+            record.replay().play_to_end().err(),
+            Some(ReplayError::IllegalSetup(2, Some(Color::White), (4u8, 4u8).into(), Illegal::Occupied))
+        );
+    }
+
+    /// ```
+    /// x o
+    /// o .
+    /// ```
+    #[test]
+    fn replay_places_setup_stones_without_capturing() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_setup(Color::Black, (0u8, 0u8).into());
+        record.push_setup(Color::White, (1u8, 0u8).into());
+        record.push_clear((1u8, 0u8).into());
+        record.push_setup(Color::White, (0u8, 1u8).into());
+        record.push_setup(Color::White, (1u8, 0u8).into());
+
+        assert_eq!(
+            record.replay().play_to_end().err(),
+            Some(ReplayError::IllegalSetup(1, Some(Color::White), (1u8, 0u8).into(), Illegal::Suicide))
+        );
+
+        let mut record = GameRecord::new(9, 9);
+        record.push_setup(Color::Black, (0u8, 0u8).into());
+        record.push_setup(Color::White, (1u8, 0u8).into());
+        record.push_clear((1u8, 0u8).into());
+        record.push_setup(Color::White, (0u8, 1u8).into());
+        record.push_move(Move::play(Color::White, (1u8, 0u8).into()));
+
+        let mut replay = record.replay();
+
+        assert_eq!(replay.next(), Some(Ok(Move::play(Color::White, (1u8, 0u8).into()))));
+        assert_eq!(replay.goban().at((0u8, 0u8).into()), None);
+        assert_eq!(replay.captures(Color::White), 1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Color, Point};
+use super::{Action, FormatError, GameRecord, Move};
+
+/// Returns the result of the game from the `Winner` header value, e.g. `W,R`
+/// or `B,3.5`.
+fn parse_result(value: &str) -> Option<String> {
+    let (winner, margin) = value.split_once(',').unwrap_or((value, ""));
+    let winner = match winner.trim() {
+        "B" => 'B',
+        "W" => 'W',
+        _ => { return None },
+    };
+
+    Some(match margin.trim() {
+        "R" | "Resign" => format!("{}+R", winner),
+        "T" | "Time" => format!("{}+T", winner),
+        margin => match margin.parse::<f32>() {
+            Ok(margin) => format!("{}+{}", winner, margin),
+            Err(_) => format!("{}+", winner),
+        }
+    })
+}
+
+fn parse_rules(value: &str) -> &str {
+    match value.trim() {
+        "JPN" => "Japanese",
+        "CHN" => "Chinese",
+        "KOR" => "Korean",
+        value => value,
+    }
+}
+
+impl GameRecord {
+    /// Returns the record of the given PandaNet `.ugf` game `text`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` -
+    ///
+    pub fn from_ugf(text: &str) -> Result<Self, FormatError> {
+        let mut section = "";
+        let mut record = None;
+        let mut header = vec! [];
+
+        for (i, line) in text.lines().map(|line| line.trim()).enumerate() {
+            let invalid_line = || FormatError::InvalidLine(i + 1, line.into());
+
+            if line.starts_with('[') && line.ends_with(']') {
+                section = &line[1..line.len()-1];
+                continue;
+            }
+
+            match section {
+                "Header" => {
+                    if let Some((key, value)) = line.split_once('=') {
+                        header.push((i, key.trim(), value.trim()));
+                    }
+                },
+                "Data" if !line.is_empty() => {
+                    let record = match record.as_mut() {
+                        Some(record) => record,
+                        None => { record.insert(Self::from_ugf_header(&header)?) }
+                    };
+                    let size = record.width();
+                    let fields = line.split(',').map(|f| f.trim()).collect::<Vec<_>>();
+                    let (at, color, move_number) = match fields[..] {
+                        [at, color, move_number, ..] => (at.as_bytes(), color, move_number),
+                        _ => { return Err(invalid_line()) }
+                    };
+                    let color = match color.chars().next() {
+                        Some('B') => Color::Black,
+                        Some('W') => Color::White,
+                        _ => { return Err(invalid_line()) }
+                    };
+
+                    // columns start from the left and rows from the bottom,
+                    // anything outside of the board is a pass
+                    let at = match at {
+                        &[x, y] => {
+                            let x = x.wrapping_sub(b'A') as usize;
+                            let y = size.wrapping_sub(1).wrapping_sub(y.wrapping_sub(b'A') as usize);
+
+                            if x < size && y < size { Some(Point::from((x, y))) } else { None }
+                        },
+                        _ => { return Err(invalid_line()) }
+                    };
+
+                    match (at, move_number) {
+                        (Some(at), "0") => record.push_setup(color, at),
+                        (Some(at), _) => record.push_move(Move::play(color, at)),
+                        (None, _) => record.push_move(Move::pass(color)),
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        let mut record = match record {
+            Some(record) => record,
+            None => Self::from_ugf_header(&header)?,
+        };
+
+        // older files do not list the handicap stones as part of the moves
+        if record.handicap() >= 2 && !record.actions().iter().any(|action| matches!(action, Action::Setup(_, _))) {
+            let moves = std::mem::take(&mut record.actions);
+
+            if !record.push_fixed_handicap() {
+                return Err(FormatError::Missing("Hdcp"));
+            }
+
+            record.actions.extend(moves);
+        }
+
+        Ok(record)
+    }
+
+    fn from_ugf_header(header: &[(usize, &str, &str)]) -> Result<Self, FormatError> {
+        let invalid_line = |&(i, key, value): &(usize, &str, &str)| FormatError::InvalidLine(i + 1, format!("{}={}", key, value));
+        let get = |name: &'static str| header.iter().find(|(_, key, _)| *key == name).ok_or(FormatError::Missing(name));
+
+        let size_line = get("Size")?;
+        let size = size_line.2.parse::<usize>().map_err(|_| invalid_line(size_line))?;
+        if !(2..=25).contains(&size) {
+            return Err(invalid_line(size_line));
+        }
+
+        let mut record = GameRecord::new(size, size);
+
+        for line in header {
+            let (_, key, value) = *line;
+
+            match key {
+                "PlayerB" => { record.set_black_name(value.split(',').next().unwrap_or("").trim()) },
+                "PlayerW" => { record.set_white_name(value.split(',').next().unwrap_or("").trim()) },
+                "Rule" => { record.set_rules(parse_rules(value)) },
+                "Winner" => {
+                    if let Some(result) = parse_result(value) {
+                        record.set_result(result);
+                    }
+                },
+                "Hdcp" => {
+                    let (handicap, komi) = value.split_once(',').unwrap_or((value, "0"));
+
+                    record.set_handicap(handicap.trim().parse().map_err(|_| invalid_line(line))?);
+                    record.set_komi(komi.trim().parse().map_err(|_| invalid_line(line))?);
+                },
+                _ => {}
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UGF: &str = "[Header]
+Lang=JPN
+PlayerB=Black,5d,,
+PlayerW=White,6d,,
+Hdcp=2,0.5
+Size=19
+Winner=B,3.5
+Rule=JPN
+[Data]
+DD,B1,0,0
+PP,B1,0,0
+QQ,W2,1,0
+YA,B1,2,0
+[Figure]
+";
+
+    #[test]
+    fn from_ugf_reads_game() {
+        let record = GameRecord::from_ugf(UGF).unwrap();
+
+        assert_eq!(record.black_name(), Some("Black"));
+        assert_eq!(record.white_name(), Some("White"));
+        assert_eq!(record.result(), Some("B+3.5"));
+        assert_eq!(record.rules(), Some("Japanese"));
+        assert_eq!(record.komi(), 0.5);
+        assert_eq!(record.handicap(), 2);
+        assert_eq!(record.actions(), &[
+            Action::Setup(Color::Black, (3u8, 15u8).into()),
+            Action::Setup(Color::Black, (15u8, 3u8).into()),
+            Action::Move(Move::play(Color::White, (16u8, 2u8).into())),
+            Action::Move(Move::pass(Color::Black)),
+        ]);
+    }
+
+    #[test]
+    fn from_ugf_places_missing_handicap_stones() {
+        let record = GameRecord::from_ugf("[Header]\nHdcp=2,0.5\nSize=19\n[Data]\nQQ,W2,1,0\n").unwrap();
+
+        assert_eq!(record.actions().len(), 3);
+        assert_eq!(record.moves().count(), 1);
+    }
+
+    #[test]
+    fn from_ugf_rejects_missing_size() {
+        assert_eq!(GameRecord::from_ugf("[Header]\nHdcp=0,6.5\n[Data]\n"), Err(FormatError::Missing("Size")));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{Color, Point};
+use super::search_tree::SearchTree;
+use std::{fmt::Debug, sync::Arc};
+
+/// The statistics of every candidate move at a node of the search tree,
+/// which can be walked by following `MoveAnalysis::children`.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub(super) to_move: Color,
+    pub(super) total_sims: u32,
+    pub(super) moves: Vec<MoveAnalysis>,
+}
+
+impl Analysis {
+    /// Returns the color of the player to move at this node.
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    /// Returns the number of probes through this node.
+    pub fn total_sims(&self) -> u32 {
+        self.total_sims
+    }
+
+    /// Returns every candidate move, ordered by the number of visits.
+    pub fn moves(&self) -> &[MoveAnalysis] {
+        &self.moves
+    }
+
+    /// Returns the most visited candidate move, or `None` if the node has not
+    /// been probed yet.
+    pub fn best(&self) -> Option<&MoveAnalysis> {
+        self.moves.first().filter(|mv| mv.visits > 0)
+    }
+
+    /// Returns the analysis of the given candidate move `at`, where `None` is
+    /// a pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn get(&self, at: Option<Point>) -> Option<&MoveAnalysis> {
+        self.moves.iter().find(|mv| mv.at == at)
+    }
+}
+
+/// The statistics of a single candidate move, from the perspective of the
+/// player making the move.
+#[derive(Clone)]
+pub struct MoveAnalysis {
+    pub(super) at: Option<Point>,
+    pub(super) visits: u32,
+    pub(super) mean: f32,
+    pub(super) variance: f32,
+    pub(super) ucb: f32,
+    pub(super) prior: f32,
+    pub(super) pv: Vec<Option<Point>>,
+    pub(super) child: Option<Arc<SearchTree>>,
+}
+
+impl Debug for MoveAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MoveAnalysis")
+            .field("at", &self.at)
+            .field("visits", &self.visits)
+            .field("mean", &self.mean)
+            .field("variance", &self.variance)
+            .field("ucb", &self.ucb)
+            .field("prior", &self.prior)
+            .field("pv", &self.pv)
+            .finish()
+    }
+}
+
+impl MoveAnalysis {
+    /// Returns the move, or `None` if it is a pass.
+    pub fn at(&self) -> Option<Point> {
+        self.at
+    }
+
+    pub fn visits(&self) -> u32 {
+        self.visits
+    }
+
+    /// Returns the mean probability that the player making this move wins.
+    pub fn mean(&self) -> f32 {
+        self.mean
+    }
+
+    /// Returns the variance of the probability that the player making this
+    /// move wins, or `0` if the move has been visited fewer than two times.
+    pub fn variance(&self) -> f32 {
+        self.variance
+    }
+
+    /// Returns the value that is maximized when selecting the next move to
+    /// probe, which is either the UCB1 or the PUCT value of the move.
+    pub fn ucb(&self) -> f32 {
+        self.ucb
+    }
+
+    /// Returns the prior probability of the move.
+    pub fn prior(&self) -> f32 {
+        self.prior
+    }
+
+    /// Returns the principal variation, i.e. this move followed by the most
+    /// visited move at every following node, where `None` is a pass.
+    pub fn pv(&self) -> &[Option<Point>] {
+        &self.pv
+    }
+
+    /// Returns the analysis of the node after this move, or `None` if it has
+    /// not been expanded yet.
+    pub fn children(&self) -> Option<Analysis> {
+        self.child.as_ref().map(|child| child.analysis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Goban, score::{GameState, ScoreOptions, Search}};
+    use super::*;
+
+    #[test]
+    fn analysis_describes_root_and_children() {
+        let options = ScoreOptions::new().with_max_sims(300).with_min_visits(1_000);
+        let mut search = Search::new(GameState::new(Goban::new(3, 3), Color::Black), options);
+        search.run();
+
+        let analysis = search.analysis();
+        assert_eq!(analysis.to_move(), Color::Black);
+        assert_eq!(analysis.total_sims(), 300);
+        assert_eq!(analysis.moves().len(), 10);
+        assert_eq!(analysis.moves().iter().map(|mv| mv.visits()).sum::<u32>(), 300);
+        assert!(analysis.moves().windows(2).all(|pair| pair[0].visits() >= pair[1].visits()));
+
+        let best = analysis.best().unwrap();
+        assert_eq!(best.at(), search.best_move());
+        assert_eq!(best.pv().first(), Some(&best.at()));
+        assert!(best.ucb() > 0.0 && best.prior() > 0.0);
+        assert_eq!(analysis.get(best.at()).map(|mv| mv.visits()), Some(best.visits()));
+
+        let children = best.children().unwrap();
+        assert_eq!(children.to_move(), Color::White);
+        assert_eq!(children.total_sims(), best.visits() - 1);
+        assert_eq!(children.best().map(|mv| mv.at()), best.pv().get(1).copied());
+    }
+}
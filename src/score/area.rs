@@ -0,0 +1,209 @@
+use crate::{array2d::Array2D, Color, Goban, Point};
+
+/// Returns the empty region that contains `at`, and the color of the
+/// stones it reaches if they are all of the same color, where `stone_at`
+/// returns the stone at each point.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `at` -
+/// * `visited` -
+/// * `stone_at` -
+///
+pub(super) fn flood_fill(
+    goban: &Goban,
+    at: Point,
+    visited: &mut Array2D<bool>,
+    stone_at: impl Fn(Point) -> Option<Color>,
+) -> (Vec<Point>, Option<Color>)
+{
+    let mut region = vec! [at];
+    let mut reaches_black = false;
+    let mut reaches_white = false;
+    let mut i = 0;
+
+    visited[(at.x(), at.y())] = true;
+
+    while i < region.len() {
+        for neighbour in region[i].neighbours().filter(|&n| goban[n].is_valid()) {
+            match stone_at(neighbour) {
+                Some(Color::Black) => { reaches_black = true },
+                Some(Color::White) => { reaches_white = true },
+                None if !visited[(neighbour.x(), neighbour.y())] => {
+                    visited[(neighbour.x(), neighbour.y())] = true;
+                    region.push(neighbour);
+                },
+                None => {},
+            }
+        }
+
+        i += 1;
+    }
+
+    let owner = match (reaches_black, reaches_white) {
+        (true, false) => Some(Color::Black),
+        (false, true) => Some(Color::White),
+        _ => None,
+    };
+
+    (region, owner)
+}
+
+/// The area score of a position according to the Tromp-Taylor rules, where a
+/// player's score is the number of their stones on the board, plus the
+/// number of empty points that only reach stones of their color. Every stone
+/// on the board is considered alive.
+#[derive(Clone)]
+pub struct AreaScore {
+    owners: Array2D<Option<Color>>,
+    black: usize,
+    white: usize,
+    komi: f32,
+}
+
+impl AreaScore {
+    /// Returns the area score of the given position `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `komi` -
+    ///
+    pub fn new(goban: &Goban, komi: f32) -> Self {
+        let mut owners = Array2D::new(goban.width() + 2, goban.height() + 2, None);
+        let mut visited = Array2D::new(goban.width() + 2, goban.height() + 2, false);
+        let mut black = 0;
+        let mut white = 0;
+
+        for at in goban.iter() {
+            match goban.at(at) {
+                Some(color) => {
+                    owners[(at.x(), at.y())] = Some(color);
+                    if color == Color::Black { black += 1 } else { white += 1 }
+                },
+                None if !visited[(at.x(), at.y())] => {
+                    let (region, owner) = flood_fill(goban, at, &mut visited, |other| goban.at(other));
+
+                    for &other in &region {
+                        owners[(other.x(), other.y())] = owner;
+                    }
+
+                    match owner {
+                        Some(Color::Black) => { black += region.len() },
+                        Some(Color::White) => { white += region.len() },
+                        None => {},
+                    }
+                },
+                None => {},
+            }
+        }
+
+        Self { owners, black, white, komi }
+    }
+
+    /// Returns the color that owns the given point `at`, which is either the
+    /// color of the stone at that point, or the color of the stones that
+    /// surround it. Returns `None` for neutral points.
+    pub fn owner_at(&self, at: Point) -> Option<Color> {
+        self.owners[(at.x(), at.y())]
+    }
+
+    /// Returns the area of black.
+    pub fn black(&self) -> usize {
+        self.black
+    }
+
+    /// Returns the area of white, excluding komi.
+    pub fn white(&self) -> usize {
+        self.white
+    }
+
+    /// Returns the number of points that belong to neither player.
+    pub fn neutral(&self) -> usize {
+        (self.owners.width() - 2) * (self.owners.height() - 2) - self.black - self.white
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+
+    /// Returns the number of points black is ahead by, after komi.
+    pub fn margin(&self) -> f32 {
+        self.black as f32 - self.white as f32 - self.komi
+    }
+
+    /// Returns the winner, or `None` if the game is a draw.
+    pub fn winner(&self) -> Option<Color> {
+        let margin = self.margin();
+
+        if margin > 0.0 {
+            Some(Color::Black)
+        } else if margin < 0.0 {
+            Some(Color::White)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the result in the SGF `RE` notation, e.g. `W+6.5` or `0` for a
+    /// draw.
+    pub fn result(&self) -> String {
+        match self.winner() {
+            Some(Color::Black) => format!("B+{}", self.margin()),
+            Some(Color::White) => format!("W+{}", -self.margin()),
+            None => "0".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ```
+    /// . . x o .
+    /// . . x o .
+    /// x x x o .
+    /// o o o o .
+    /// . . . . .
+    /// ```
+    #[test]
+    fn regions_reaching_one_color_are_owned() {
+        let mut goban = Goban::new(5, 5);
+        for at in [(2u8, 0u8), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            goban.play(at.into(), Color::Black);
+        }
+        for at in [(3u8, 0u8), (3, 1), (3, 2), (0, 3), (1, 3), (2, 3), (3, 3)] {
+            goban.play(at.into(), Color::White);
+        }
+
+        let area = AreaScore::new(&goban, 0.5);
+
+        assert_eq!(area.black(), 9);
+        assert_eq!(area.white(), 16);
+        assert_eq!(area.neutral(), 0);
+        assert_eq!(area.owner_at((0u8, 0u8).into()), Some(Color::Black));
+        assert_eq!(area.owner_at((4u8, 4u8).into()), Some(Color::White));
+        assert_eq!(area.margin(), -7.5);
+        assert_eq!(area.winner(), Some(Color::White));
+    }
+
+    /// ```
+    /// . . . .
+    /// . x . o
+    /// . . . .
+    /// ```
+    #[test]
+    fn regions_reaching_both_colors_are_neutral() {
+        let mut goban = Goban::new(4, 3);
+        goban.play((1u8, 1u8).into(), Color::Black);
+        goban.play((3u8, 1u8).into(), Color::White);
+
+        let area = AreaScore::new(&goban, 0.0);
+
+        assert_eq!((area.black(), area.white(), area.neutral()), (1, 1, 10));
+        assert_eq!(area.owner_at((0u8, 0u8).into()), None);
+        assert_eq!(area.winner(), None);
+    }
+}
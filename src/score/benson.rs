@@ -0,0 +1,216 @@
+use crate::{array2d::Array2D, Color, Goban, Point};
+use super::chain::chains;
+
+/// The chains of a single color that are unconditionally alive according to
+/// Benson's algorithm, i.e. chains that cannot be captured even if their
+/// owner passes every move, together with their vital regions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Benson {
+    chains: Vec<Vec<Point>>,
+    regions: Vec<Vec<Point>>,
+}
+
+impl Benson {
+    /// Returns the unconditionally alive chains of the given color `color` in
+    /// the position `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `color` -
+    ///
+    pub fn new(goban: &Goban, color: Color) -> Self {
+        let chains = chains(goban, color);
+        let mut chain_of = Array2D::new(goban.width() + 2, goban.height() + 2, usize::MAX);
+        let mut visited = Array2D::new(goban.width() + 2, goban.height() + 2, false);
+
+        for (i, chain) in chains.iter().enumerate() {
+            for &at in chain.stones() {
+                chain_of[(at.x(), at.y())] = i;
+            }
+        }
+
+        // every maximal connected set of points not occupied by `color`,
+        // with the chains that border it, and the chains it is vital to
+        let mut regions = vec! [];
+
+        for at in goban.iter() {
+            if goban.at(at) == Some(color) || visited[(at.x(), at.y())] {
+                continue;
+            }
+
+            let mut points = vec! [at];
+            let mut neighbours = vec! [];
+            let mut i = 0;
+
+            visited[(at.x(), at.y())] = true;
+
+            while i < points.len() {
+                for other in points[i].neighbours().filter(|&n| goban[n].is_valid()) {
+                    if goban.at(other) == Some(color) {
+                        let chain = chain_of[(other.x(), other.y())];
+
+                        if !neighbours.contains(&chain) {
+                            neighbours.push(chain);
+                        }
+                    } else if !visited[(other.x(), other.y())] {
+                        visited[(other.x(), other.y())] = true;
+                        points.push(other);
+                    }
+                }
+
+                i += 1;
+            }
+
+            let vital_to = neighbours.iter()
+                .copied()
+                .filter(|&chain| {
+                    points.iter()
+                        .filter(|&&at| goban.at(at).is_none())
+                        .all(|at| chains[chain].liberties().contains(at))
+                })
+                .collect::<Vec<_>>();
+
+            regions.push((points, neighbours, vital_to));
+        }
+
+        let mut is_chain_alive = vec! [true; chains.len()];
+        let mut is_region_alive = vec! [true; regions.len()];
+
+        loop {
+            let mut is_changed = false;
+
+            for (chain, is_alive) in is_chain_alive.iter_mut().enumerate().filter(|(_, is_alive)| **is_alive) {
+                let num_vital = regions.iter()
+                    .zip(is_region_alive.iter())
+                    .filter(|((_, _, vital_to), &is_alive)| is_alive && vital_to.contains(&chain))
+                    .count();
+
+                if num_vital < 2 {
+                    *is_alive = false;
+                    is_changed = true;
+                }
+            }
+
+            for ((_, neighbours, _), is_alive) in regions.iter().zip(is_region_alive.iter_mut()).filter(|(_, is_alive)| **is_alive) {
+                if neighbours.iter().any(|&chain| !is_chain_alive[chain]) {
+                    *is_alive = false;
+                    is_changed = true;
+                }
+            }
+
+            if !is_changed {
+                break;
+            }
+        }
+
+        Self {
+            chains: chains.iter()
+                .zip(is_chain_alive.iter())
+                .filter(|(_, &is_alive)| is_alive)
+                .map(|(chain, _)| chain.stones().to_vec())
+                .collect(),
+            regions: regions.into_iter()
+                .zip(is_region_alive.iter())
+                .filter(|((_, _, vital_to), &is_alive)| is_alive && vital_to.iter().any(|&chain| is_chain_alive[chain]))
+                .map(|((points, _, _), _)| points)
+                .collect(),
+        }
+    }
+
+    /// Returns the stones of every unconditionally alive chain.
+    pub fn chains(&self) -> &[Vec<Point>] {
+        &self.chains
+    }
+
+    /// Returns the vital regions of the unconditionally alive chains. Every
+    /// empty point in these regions is a liberty of an alive chain, and any
+    /// stone of the opponent inside them is dead.
+    pub fn regions(&self) -> &[Vec<Point>] {
+        &self.regions
+    }
+
+    /// Returns whether the stone at `at` belongs to an unconditionally alive
+    /// chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn is_alive(&self, at: Point) -> bool {
+        self.chains.iter().any(|chain| chain.contains(&at))
+    }
+
+    /// Returns whether the given point `at` is inside one of the vital
+    /// regions.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn is_vital(&self, at: Point) -> bool {
+        self.regions.iter().any(|region| region.contains(&at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ```
+    /// . x . x
+    /// x x x x
+    /// ```
+    #[test]
+    fn two_eyes_are_alive() {
+        let mut goban = Goban::new(4, 2);
+        for at in [(1u8, 0u8), (3, 0), (0, 1), (1, 1), (2, 1), (3, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+
+        let benson = Benson::new(&goban, Color::Black);
+
+        assert_eq!(benson.chains().len(), 1);
+        assert_eq!(benson.regions().len(), 2);
+        assert!(benson.is_alive((1u8, 1u8).into()));
+        assert!(benson.is_vital((0u8, 0u8).into()));
+        assert!(Benson::new(&goban, Color::White).chains().is_empty());
+    }
+
+    /// ```
+    /// . x . .
+    /// x x . .
+    /// . . . .
+    /// ```
+    #[test]
+    fn one_eye_is_not_alive() {
+        let mut goban = Goban::new(4, 3);
+        for at in [(1u8, 0u8), (0, 1), (1, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+
+        let benson = Benson::new(&goban, Color::Black);
+
+        assert!(benson.chains().is_empty());
+        assert!(benson.regions().is_empty());
+    }
+
+    /// ```
+    /// . x o . x .
+    /// x x x x x x
+    /// ```
+    #[test]
+    fn opponent_stones_inside_vital_region() {
+        let mut goban = Goban::new(6, 2);
+        for at in [(1u8, 0u8), (4, 0), (0, 1), (1, 1), (2, 1), (3, 1), (4, 1), (5, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((2u8, 0u8).into(), Color::White);
+
+        let benson = Benson::new(&goban, Color::Black);
+
+        assert_eq!(benson.chains().len(), 1);
+        assert_eq!(benson.regions().len(), 3);
+        assert!(benson.is_vital((2u8, 0u8).into()));
+    }
+}
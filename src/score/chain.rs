@@ -0,0 +1,86 @@
+use crate::{array2d::Array2D, Color, Goban, Point};
+
+/// A maximal set of connected stones of the same color, together with its
+/// liberties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Chain {
+    color: Color,
+    stones: Vec<Point>,
+    liberties: Vec<Point>,
+}
+
+impl Chain {
+    /// Returns the chain that contains the stone at `at`, or `None` if the
+    /// point is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `at` -
+    ///
+    pub(crate) fn at(goban: &Goban, at: Point) -> Option<Self> {
+        let mut visited = Array2D::new(goban.width() + 2, goban.height() + 2, false);
+
+        Self::flood_fill(goban, at, &mut visited)
+    }
+
+    fn flood_fill(goban: &Goban, at: Point, visited: &mut Array2D<bool>) -> Option<Self> {
+        let color = goban.at(at)?;
+        let mut stones = vec! [at];
+        let mut liberties = vec! [];
+        let mut i = 0;
+
+        visited[(at.x(), at.y())] = true;
+
+        while i < stones.len() {
+            for neighbour in stones[i].neighbours().filter(|&n| goban[n].is_valid()) {
+                match goban.at(neighbour) {
+                    None if !liberties.contains(&neighbour) => { liberties.push(neighbour) },
+                    Some(other) if other == color && !visited[(neighbour.x(), neighbour.y())] => {
+                        visited[(neighbour.x(), neighbour.y())] = true;
+                        stones.push(neighbour);
+                    },
+                    _ => {},
+                }
+            }
+
+            i += 1;
+        }
+
+        Some(Self { color, stones, liberties })
+    }
+
+    pub(crate) fn color(&self) -> Color {
+        self.color
+    }
+
+    pub(crate) fn stones(&self) -> &[Point] {
+        &self.stones
+    }
+
+    pub(crate) fn liberties(&self) -> &[Point] {
+        &self.liberties
+    }
+}
+
+/// Returns every chain of the given color `color` on the board.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `color` -
+///
+pub(crate) fn chains(goban: &Goban, color: Color) -> Vec<Chain> {
+    let mut visited = Array2D::new(goban.width() + 2, goban.height() + 2, false);
+
+    goban.iter()
+        .filter(|&at| goban.at(at) == Some(color))
+        .filter_map(|at| {
+            if visited[(at.x(), at.y())] {
+                None
+            } else {
+                Chain::flood_fill(goban, at, &mut visited)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,437 @@
+use crate::{Color, Goban, Point};
+use super::{
+    area::AreaScore,
+    normal::Normal,
+    playout::{play_out_with, LeafEvaluator, UniformPolicy},
+    random::Random,
+};
+use std::{
+    sync::{Arc, Condvar, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// A position at a leaf of the search tree that should be evaluated.
+#[derive(Clone)]
+pub struct Leaf {
+    goban: Goban,
+    to_move: Color,
+    last_move: Option<Point>,
+    pass_count: u8,
+    komi: f32,
+}
+
+impl Leaf {
+    /// Returns a leaf for the given position `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `last_move` - the move that led to this position, or `None` if it
+    ///   was a pass
+    /// * `pass_count` - the number of consecutive passes before this position
+    /// * `komi` -
+    ///
+    pub fn new(goban: Goban, to_move: Color, last_move: Option<Point>, pass_count: u8, komi: f32) -> Self {
+        Self { goban, to_move, last_move, pass_count, komi }
+    }
+
+    pub fn goban(&self) -> &Goban {
+        &self.goban
+    }
+
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    pub fn last_move(&self) -> Option<Point> {
+        self.last_move
+    }
+
+    /// Returns the number of consecutive passes that led to this position,
+    /// where `2` means that the game is over and the position should be
+    /// scored as it is.
+    pub fn pass_count(&self) -> u8 {
+        self.pass_count
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+}
+
+/// The evaluation of a leaf, i.e. the probability that the player to move
+/// wins, and optionally the expected margin, a policy, and the ownership of
+/// every point.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Evaluation {
+    pub(super) value: f32,
+    pub(super) margin: f32,
+    pub(super) policy: Option<Vec<f32>>,
+    pub(super) ownership: Option<Vec<f32>>,
+    pub(super) moves: Vec<(Point, Color)>,
+}
+
+impl Evaluation {
+    /// Returns an evaluation where the player to move wins with the given
+    /// probability `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` -
+    ///
+    pub fn new(value: f32) -> Self {
+        Self { value, ..Self::default() }
+    }
+
+    /// Returns the evaluation of the Tromp-Taylor area score of the given
+    /// position `goban`, as seen by the player `to_move`. Points that are
+    /// still neutral are assumed to be split evenly between the players,
+    /// with some uncertainty.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `komi` -
+    ///
+    pub fn score(goban: &Goban, to_move: Color, komi: f32) -> Self {
+        let area = AreaScore::new(goban, komi);
+        let neutral = area.neutral();
+        let black_value = if neutral == 0 {
+            if area.margin() > 0.0 { 1.0 } else { 0.0 }
+        } else {
+            let mean = -area.margin();
+            let std = 2.0 * neutral as f32 / 12.0;
+
+            Normal::new(mean, std).cdf(0.0)
+        };
+        let ownership = goban.iter()
+            .map(|at| match area.owner_at(at) {
+                Some(Color::Black) => 1.0,
+                Some(Color::White) => -1.0,
+                None => 0.0,
+            })
+            .collect();
+
+        Self::new(if to_move == Color::Black { black_value } else { 1.0 - black_value })
+            .with_margin(area.margin())
+            .with_ownership(ownership)
+    }
+
+    /// Set the number of points black is expected to be ahead by, after
+    /// komi.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` -
+    ///
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the policy, which has one weight for every point in the same
+    /// order as `Goban::iter`, followed by the weight of passing. The policy
+    /// is used as the prior of the leaf once it is expanded.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` -
+    ///
+    pub fn with_policy(mut self, policy: Vec<f32>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Set the ownership, which has one value in the range `[-1, 1]` for
+    /// every point in the same order as `Goban::iter`, where `1` means the
+    /// point belongs to black.
+    ///
+    /// # Arguments
+    ///
+    /// * `ownership` -
+    ///
+    pub fn with_ownership(mut self, ownership: Vec<f32>) -> Self {
+        self.ownership = Some(ownership);
+        self
+    }
+
+    /// Set the stones that were played to reach the evaluated position,
+    /// latest first, which are used for the all-moves-as-first statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` -
+    ///
+    pub(super) fn with_moves(mut self, moves: Vec<(Point, Color)>) -> Self {
+        self.moves = moves;
+        self
+    }
+
+    /// Returns the probability that the player to move wins.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Returns the number of points black is expected to be ahead by.
+    pub fn margin(&self) -> f32 {
+        self.margin
+    }
+
+    pub fn policy(&self) -> Option<&[f32]> {
+        self.policy.as_deref()
+    }
+
+    pub fn ownership(&self) -> Option<&[f32]> {
+        self.ownership.as_deref()
+    }
+}
+
+/// Evaluates the leaves of the search tree.
+pub trait Evaluator: Send + Sync {
+    /// Returns the evaluation of the given leaf `leaf`.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaf` -
+    /// * `rng` -
+    ///
+    fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation;
+
+    /// Returns the evaluation of every leaf in `leaves`, in the same order.
+    /// Evaluators that are more efficient when given many positions at once,
+    /// such as neural networks, should override this.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaves` -
+    /// * `rng` -
+    ///
+    fn evaluate_batch(&self, leaves: &[Leaf], rng: &mut Random) -> Vec<Evaluation> {
+        leaves.iter().map(|leaf| self.evaluate(leaf, rng)).collect()
+    }
+}
+
+impl Evaluator for LeafEvaluator {
+    fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+        let mut goban = leaf.goban.clone();
+        let mut moves = vec! [];
+        let on_move = |at, color| moves.push((at, color));
+
+        match self {
+            LeafEvaluator::Score => {},
+            LeafEvaluator::Playout => {
+                play_out_with(&mut goban, leaf.to_move, leaf.last_move, leaf.pass_count, &UniformPolicy, rng, on_move);
+            },
+            LeafEvaluator::HeavyPlayout(policy) => {
+                play_out_with(&mut goban, leaf.to_move, leaf.last_move, leaf.pass_count, policy, rng, on_move);
+            },
+        }
+
+        moves.reverse();
+        Evaluation::score(&goban, leaf.to_move, leaf.komi).with_moves(moves)
+    }
+}
+
+/// A single queued leaf, and the slot its evaluation is written to.
+type Request = (Leaf, Arc<(Mutex<Option<Evaluation>>, Condvar)>);
+
+/// An evaluator that queues the leaves of concurrent probes, and evaluates
+/// them together using `Evaluator::evaluate_batch` of the inner evaluator
+/// once the batch is full. If a batch does not fill up within the timeout,
+/// then the leaves that are queued so far are evaluated anyway.
+///
+/// Every probing thread waits for its own leaf to be evaluated, so a batch
+/// never holds more leaves than there are search threads. The number of
+/// threads in `ScoreOptions` should therefore be at least the batch size,
+/// or the batches will never fill up and every leaf waits for the timeout.
+/// With a zero timeout every leaf is evaluated as soon as it is queued,
+/// together with any other leaves that are already waiting.
+pub struct BatchEvaluator<E: Evaluator> {
+    inner: E,
+    batch_size: usize,
+    timeout: Duration,
+    queue: Mutex<Vec<Request>>,
+}
+
+impl<E: Evaluator> BatchEvaluator<E> {
+    /// Returns an evaluator that evaluates up to `batch_size` leaves at a
+    /// time using `inner`, waiting at most one millisecond for a batch to
+    /// fill up.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` -
+    /// * `batch_size` -
+    ///
+    pub fn new(inner: E, batch_size: usize) -> Self {
+        Self {
+            inner,
+            batch_size: batch_size.max(1),
+            timeout: Duration::from_millis(1),
+            queue: Mutex::new(vec! []),
+        }
+    }
+
+    /// Set how long to wait for a batch to fill up, which should be zero if
+    /// there are fewer search threads than the batch size.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` -
+    ///
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Evaluate every queued leaf, and wake up the threads waiting for them.
+    fn flush(&self, requests: Vec<Request>, rng: &mut Random) {
+        let (leaves, slots): (Vec<_>, Vec<_>) = requests.into_iter().unzip();
+        let evaluations = self.inner.evaluate_batch(&leaves, rng);
+
+        for (slot, evaluation) in slots.into_iter().zip(evaluations) {
+            let (result, is_ready) = &*slot;
+
+            *result.lock().unwrap_or_else(PoisonError::into_inner) = Some(evaluation);
+            is_ready.notify_all();
+        }
+    }
+}
+
+impl<E: Evaluator> Evaluator for BatchEvaluator<E> {
+    fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+        let slot = Arc::new((Mutex::new(None), Condvar::new()));
+        let full_batch = {
+            let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+            queue.push((leaf.clone(), slot.clone()));
+
+            if queue.len() >= self.batch_size || self.timeout.is_zero() {
+                Some(std::mem::take(&mut *queue))
+            } else {
+                None
+            }
+        };
+
+        if let Some(requests) = full_batch {
+            self.flush(requests, rng);
+        }
+
+        let (result, is_ready) = &*slot;
+        let mut evaluation = result.lock().unwrap_or_else(PoisonError::into_inner);
+
+        loop {
+            if let Some(evaluation) = evaluation.take() {
+                return evaluation;
+            }
+
+            let (guard, timeout) = is_ready.wait_timeout(evaluation, self.timeout).unwrap_or_else(PoisonError::into_inner);
+            evaluation = guard;
+
+            if timeout.timed_out() && evaluation.is_none() {
+                drop(evaluation);
+
+                // evaluate the partial batch ourselves, unless another thread
+                // has already taken our leaf
+                let requests = std::mem::take(&mut *self.queue.lock().unwrap_or_else(PoisonError::into_inner));
+                if !requests.is_empty() {
+                    self.flush(requests, rng);
+                }
+
+                evaluation = result.lock().unwrap_or_else(PoisonError::into_inner);
+            }
+        }
+    }
+
+    fn evaluate_batch(&self, leaves: &[Leaf], rng: &mut Random) -> Vec<Evaluation> {
+        self.inner.evaluate_batch(leaves, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::atomic::{AtomicUsize, Ordering}, thread};
+
+    struct Counting {
+        num_batches: AtomicUsize,
+        num_leaves: AtomicUsize,
+    }
+
+    impl Evaluator for Counting {
+        fn evaluate(&self, leaf: &Leaf, _rng: &mut Random) -> Evaluation {
+            Evaluation::new(if leaf.to_move() == Color::Black { 1.0 } else { 0.0 })
+        }
+
+        fn evaluate_batch(&self, leaves: &[Leaf], rng: &mut Random) -> Vec<Evaluation> {
+            self.num_batches.fetch_add(1, Ordering::Relaxed);
+            self.num_leaves.fetch_add(leaves.len(), Ordering::Relaxed);
+
+            leaves.iter().map(|leaf| self.evaluate(leaf, rng)).collect()
+        }
+    }
+
+    /// ```
+    /// x x .
+    /// ```
+    #[test]
+    fn score_is_from_perspective_of_player_to_move() {
+        let mut goban = Goban::new(3, 1);
+        goban.play((0u8, 0u8).into(), Color::Black);
+        goban.play((1u8, 0u8).into(), Color::Black);
+
+        let black = LeafEvaluator::Score.evaluate(&Leaf::new(goban.clone(), Color::Black, None, 0, 0.5), &mut Random::new(0));
+        let white = LeafEvaluator::Score.evaluate(&Leaf::new(goban, Color::White, None, 0, 0.5), &mut Random::new(0));
+
+        assert_eq!(black.value(), 1.0);
+        assert_eq!(white.value(), 0.0);
+        assert_eq!(black.margin(), 2.5);
+        assert_eq!(black.ownership(), Some(&[1.0, 1.0, 1.0][..]));
+    }
+
+    #[test]
+    fn batch_evaluator_evaluates_together() {
+        let batch = BatchEvaluator::new(Counting { num_batches: AtomicUsize::new(0), num_leaves: AtomicUsize::new(0) }, 4)
+            .with_timeout(Duration::from_secs(10));
+
+        thread::scope(|scope| {
+            for i in 0..4 {
+                let batch = &batch;
+
+                scope.spawn(move || {
+                    let to_move = if i % 2 == 0 { Color::Black } else { Color::White };
+                    let evaluation = batch.evaluate(&Leaf::new(Goban::new(3, 3), to_move, None, 0, 0.5), &mut Random::new(i));
+
+                    assert_eq!(evaluation.value(), if to_move == Color::Black { 1.0 } else { 0.0 });
+                });
+            }
+        });
+
+        assert_eq!(batch.inner().num_batches.load(Ordering::Relaxed), 1);
+        assert_eq!(batch.inner().num_leaves.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn partial_batch_is_evaluated_without_timeout() {
+        let batch = BatchEvaluator::new(Counting { num_batches: AtomicUsize::new(0), num_leaves: AtomicUsize::new(0) }, 8)
+            .with_timeout(Duration::ZERO);
+        let evaluation = batch.evaluate(&Leaf::new(Goban::new(3, 3), Color::White, None, 0, 0.5), &mut Random::new(0));
+
+        assert_eq!(evaluation.value(), 0.0);
+        assert_eq!(batch.inner().num_batches.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn partial_batch_is_evaluated_after_timeout() {
+        let batch = BatchEvaluator::new(Counting { num_batches: AtomicUsize::new(0), num_leaves: AtomicUsize::new(0) }, 8);
+        let evaluation = batch.evaluate(&Leaf::new(Goban::new(3, 3), Color::Black, None, 0, 0.5), &mut Random::new(0));
+
+        assert_eq!(evaluation.value(), 1.0);
+        assert_eq!(batch.inner().num_leaves.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{Color, Goban, Illegal, Point, record::{GameRecord, ReplayError}};
+
+/// The rule set of a game, which decides how the final result is counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rules {
+    /// Area scoring, as used by the Chinese, AGA, New Zealand and Tromp-Taylor
+    /// rules, where stones and surrounded points both count.
+    #[default]
+    Area,
+
+    /// Territory scoring, as used by the Japanese and Korean rules, where
+    /// surrounded points and prisoners count.
+    Territory,
+}
+
+impl Rules {
+    /// Returns the rule set with the given name `name`, as found in the `RU`
+    /// property of an SGF file, or `None` if the name is not recognized.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` -
+    ///
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "chinese" | "cn" | "aga" | "nz" | "new zealand" | "tromp-taylor" | "tromp taylor" => Some(Self::Area),
+            "japanese" | "jp" | "korean" | "ko" => Some(Self::Territory),
+            _ => None,
+        }
+    }
+}
+
+/// The full state of a game that is needed to continue playing it, i.e. the
+/// position together with its ko history, the player to move, the number of
+/// consecutive passes, and the rules the game is played under.
+#[derive(Clone)]
+pub struct GameState {
+    goban: Goban,
+    to_move: Color,
+    pass_count: usize,
+    komi: f32,
+    rules: Rules,
+    black_captures: usize,
+    white_captures: usize,
+}
+
+impl GameState {
+    /// Returns the state of a game at the given position `goban`, with no
+    /// previous passes, no prisoners, no komi, and area scoring.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    ///
+    pub fn new(goban: Goban, to_move: Color) -> Self {
+        Self {
+            goban,
+            to_move,
+            pass_count: 0,
+            komi: 0.0,
+            rules: Rules::default(),
+            black_captures: 0,
+            white_captures: 0,
+        }
+    }
+
+    /// Returns the state of the game after every move in the given record
+    /// `record` has been played. The rules are taken from the record, and
+    /// default to area scoring if they are missing or not recognized.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` -
+    ///
+    pub fn from_record(record: &GameRecord) -> Result<Self, ReplayError> {
+        let mut replay = record.replay();
+        for result in replay.by_ref() {
+            result?;
+        }
+
+        Ok(Self {
+            goban: replay.goban().clone(),
+            to_move: replay.to_move(),
+            pass_count: replay.pass_count(),
+            komi: record.komi(),
+            rules: record.rules().and_then(Rules::from_name).unwrap_or_default(),
+            black_captures: replay.captures(Color::Black),
+            white_captures: replay.captures(Color::White),
+        })
+    }
+
+    /// Set the player to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `to_move` -
+    ///
+    pub fn with_to_move(mut self, to_move: Color) -> Self {
+        self.to_move = to_move;
+        self
+    }
+
+    /// Set the number of consecutive passes that preceded this position.
+    ///
+    /// # Arguments
+    ///
+    /// * `pass_count` -
+    ///
+    pub fn with_pass_count(mut self, pass_count: usize) -> Self {
+        self.pass_count = pass_count;
+        self
+    }
+
+    /// Set the komi.
+    ///
+    /// # Arguments
+    ///
+    /// * `komi` -
+    ///
+    pub fn with_komi(mut self, komi: f32) -> Self {
+        self.komi = komi;
+        self
+    }
+
+    /// Set the rules. The search always evaluates positions using area
+    /// scoring, so the rules only decide how `Score::final_territory` and
+    /// the GTP `final_score` count the final position.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` -
+    ///
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Set the number of stones captured by the given color `color`.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    /// * `captures` -
+    ///
+    pub fn with_captures(mut self, color: Color, captures: usize) -> Self {
+        match color {
+            Color::Black => { self.black_captures = captures },
+            Color::White => { self.white_captures = captures },
+        }
+        self
+    }
+
+    /// Play the given move `at` for the player to move, where `None` is a
+    /// pass, and update the number of passes and prisoners accordingly.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn play(&mut self, at: Option<Point>) -> Result<(), Illegal> {
+        if let Some(at) = at {
+            self.goban.check_legal(at, self.to_move)?;
+
+            let captures = self.goban.play(at, self.to_move);
+            self.pass_count = 0;
+
+            match self.to_move {
+                Color::Black => { self.black_captures += captures },
+                Color::White => { self.white_captures += captures },
+            }
+        } else {
+            self.pass_count += 1;
+        }
+
+        self.to_move = self.to_move.opposite();
+        Ok(())
+    }
+
+    /// Returns the current position, including its ko history.
+    pub fn goban(&self) -> &Goban {
+        &self.goban
+    }
+
+    /// Returns the color of the player whose turn it is to move.
+    pub fn to_move(&self) -> Color {
+        self.to_move
+    }
+
+    /// Returns the number of consecutive passes that preceded this position,
+    /// the game is over once it reaches `2`.
+    pub fn pass_count(&self) -> usize {
+        self.pass_count
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+
+    /// Returns the rules the game is played under, see `with_rules` for how
+    /// they are used.
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Returns the number of stones captured by the given color `color`.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    ///
+    pub fn captures(&self, color: Color) -> usize {
+        match color {
+            Color::Black => self.black_captures,
+            Color::White => self.white_captures,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::Move;
+
+    #[test]
+    fn from_name_recognizes_common_rules() {
+        assert_eq!(Rules::from_name("Chinese"), Some(Rules::Area));
+        assert_eq!(Rules::from_name("tromp-taylor"), Some(Rules::Area));
+        assert_eq!(Rules::from_name("Japanese"), Some(Rules::Territory));
+        assert_eq!(Rules::from_name("Korean"), Some(Rules::Territory));
+        assert_eq!(Rules::from_name("Ing"), None);
+    }
+
+    #[test]
+    fn from_record_tracks_passes_and_rules() {
+        let mut record = GameRecord::new(9, 9);
+        record.set_komi(6.5);
+        record.set_rules("Japanese");
+        record.push_move(Move::play(Color::Black, (4u8, 4u8).into()));
+        record.push_move(Move::pass(Color::White));
+
+        let state = GameState::from_record(&record).unwrap();
+
+        assert_eq!(state.to_move(), Color::Black);
+        assert_eq!(state.pass_count(), 1);
+        assert_eq!(state.komi(), 6.5);
+        assert_eq!(state.rules(), Rules::Territory);
+        assert_eq!(state.goban().at((4u8, 4u8).into()), Some(Color::Black));
+    }
+
+    #[test]
+    fn play_updates_state() {
+        let mut state = GameState::new(Goban::new(3, 3), Color::Black);
+
+        assert_eq!(state.play(Some((0u8, 1u8).into())), Ok(()));
+        assert_eq!(state.play(Some((0u8, 0u8).into())), Ok(()));
+        assert_eq!(state.play(Some((1u8, 0u8).into())), Ok(()));
+        assert_eq!(state.captures(Color::Black), 1);
+        assert_eq!(state.to_move(), Color::White);
+        assert_eq!(state.play(Some((1u8, 0u8).into())), Err(Illegal::Occupied));
+        assert_eq!(state.play(None), Ok(()));
+        assert_eq!(state.pass_count(), 1);
+        assert_eq!(state.to_move(), Color::Black);
+    }
+
+    /// ```
+    /// . x o .
+    /// x o . o
+    /// . x o .
+    /// ```
+    #[test]
+    fn from_record_keeps_ko_history() {
+        let mut record = GameRecord::new(4, 3);
+        for (color, at) in [
+            (Color::Black, (1u8, 0u8)), (Color::White, (2u8, 0u8)),
+            (Color::Black, (0u8, 1u8)), (Color::White, (3u8, 1u8)),
+            (Color::Black, (1u8, 2u8)), (Color::White, (2u8, 2u8)),
+            (Color::Black, (2u8, 1u8)), (Color::White, (1u8, 1u8)),
+        ] {
+            record.push_move(Move::play(color, at.into()));
+        }
+
+        let state = GameState::from_record(&record).unwrap();
+
+        assert_eq!(state.to_move(), Color::Black);
+        assert_eq!(state.captures(Color::White), 1);
+        assert!(!state.goban().is_legal((2u8, 1u8).into(), Color::Black));
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{Color, Goban, Point, pattern::is_response};
+use super::{playout::{random_move, random_move_where, PlayoutPolicy}, random::Random};
+
+/// Returns the candidate moves of a heuristic, given the previous move and the
+/// color to move.
+type Heuristic = fn(&Goban, Point, Color) -> Vec<Point>;
+
+/// Returns whether playing a stone of the given color `color` at `at` leaves
+/// the block it becomes part of with a single liberty, without capturing
+/// anything.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `at` -
+/// * `color` -
+///
+fn is_self_atari(goban: &Goban, at: Point, color: Color) -> bool {
+    let mut liberties = vec! [];
+
+    for other in at.neighbours().filter(|&n| goban[n].is_valid()) {
+        match goban.at(other) {
+            None => {
+                if !liberties.contains(&other) {
+                    liberties.push(other);
+                }
+            },
+            Some(other_color) if other_color == color => {
+                for liberty in goban.liberties_at(other) {
+                    if liberty != at && !liberties.contains(&liberty) {
+                        liberties.push(liberty);
+                    }
+                }
+            },
+            Some(_) => {
+                if goban.num_liberties_at(other) == 1 {
+                    return false;
+                }
+            },
+        }
+
+        if liberties.len() >= 2 {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// A playout policy in the style of MoGo, that prefers tactical moves and
+/// pattern responses near the previous move over random moves. Each heuristic
+/// is only tried with its own probability, so that playout speed can be traded
+/// against accuracy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeavyPolicy {
+    capture: f32,
+    escape: f32,
+    pattern: f32,
+    avoid_self_atari: f32,
+}
+
+impl Default for HeavyPolicy {
+    fn default() -> Self {
+        Self {
+            capture: 0.9,
+            escape: 0.9,
+            pattern: 0.9,
+            avoid_self_atari: 0.9,
+        }
+    }
+}
+
+impl PlayoutPolicy for HeavyPolicy {
+    fn select(&self, goban: &Goban, to_move: Color, last_move: Option<Point>, rng: &mut Random) -> Option<Point> {
+        if let Some(last_move) = last_move {
+            let heuristics: [(f32, Heuristic); 3] = [
+                (self.capture, capture_moves),
+                (self.escape, escape_moves),
+                (self.pattern, pattern_moves),
+            ];
+
+            for (probability, moves) in heuristics {
+                if rng.next_f32() < probability {
+                    let moves = moves(goban, last_move, to_move);
+
+                    if !moves.is_empty() {
+                        return Some(moves[rng.below(moves.len())]);
+                    }
+                }
+            }
+        }
+
+        if rng.next_f32() < self.avoid_self_atari {
+            random_move_where(goban, to_move, rng, |at| !is_self_atari(goban, at, to_move))
+                .or_else(|| random_move(goban, to_move, rng))
+        } else {
+            random_move(goban, to_move, rng)
+        }
+    }
+}
+
+impl HeavyPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the probability of capturing an opponent block in atari next
+    /// to the previous move.
+    pub fn capture(&self) -> f32 {
+        self.capture
+    }
+
+    /// Set the probability of capturing an opponent block in atari next to
+    /// the previous move.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` -
+    ///
+    pub fn with_capture(mut self, capture: f32) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Returns the probability of extending a block that was put in atari by
+    /// the previous move.
+    pub fn escape(&self) -> f32 {
+        self.escape
+    }
+
+    /// Set the probability of extending a block that was put in atari by the
+    /// previous move.
+    ///
+    /// # Arguments
+    ///
+    /// * `escape` -
+    ///
+    pub fn with_escape(mut self, escape: f32) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Returns the probability of playing a `3x3` pattern response around
+    /// the previous move.
+    pub fn pattern(&self) -> f32 {
+        self.pattern
+    }
+
+    /// Set the probability of playing a `3x3` pattern response around the
+    /// previous move.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` -
+    ///
+    pub fn with_pattern(mut self, pattern: f32) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Returns the probability of rejecting random moves that are
+    /// self-atari.
+    pub fn avoid_self_atari(&self) -> f32 {
+        self.avoid_self_atari
+    }
+
+    /// Set the probability of rejecting random moves that are self-atari.
+    ///
+    /// # Arguments
+    ///
+    /// * `avoid_self_atari` -
+    ///
+    pub fn with_avoid_self_atari(mut self, avoid_self_atari: f32) -> Self {
+        self.avoid_self_atari = avoid_self_atari;
+        self
+    }
+}
+
+/// Returns the moves that capture an opponent block in atari, at or around
+/// the previous move `last_move`.
+fn capture_moves(goban: &Goban, last_move: Point, to_move: Color) -> Vec<Point> {
+    let mut moves = vec! [];
+
+    for other in [last_move].into_iter().chain(last_move.surroundings()) {
+        if goban[other].is_valid() && goban.at(other) == Some(to_move.opposite()) && goban.num_liberties_at(other) == 1 {
+            let at = goban.liberties_at(other)[0];
+
+            if !moves.contains(&at) && goban.is_legal(at, to_move) {
+                moves.push(at);
+            }
+        }
+    }
+
+    moves
+}
+
+/// Returns the moves that extend an own block that was put in atari by the
+/// previous move `last_move`, without ending up in atari again.
+fn escape_moves(goban: &Goban, last_move: Point, to_move: Color) -> Vec<Point> {
+    let mut moves = vec! [];
+
+    for other in last_move.neighbours() {
+        if goban[other].is_valid() && goban.at(other) == Some(to_move) && goban.num_liberties_at(other) == 1 {
+            let at = goban.liberties_at(other)[0];
+
+            if !moves.contains(&at) && goban.is_legal(at, to_move) && !is_self_atari(goban, at, to_move) {
+                moves.push(at);
+            }
+        }
+    }
+
+    moves
+}
+
+/// Returns the empty points around the previous move `last_move` that match
+/// one of the `3x3` response patterns, and are not self-atari.
+fn pattern_moves(goban: &Goban, last_move: Point, to_move: Color) -> Vec<Point> {
+    last_move.surroundings()
+        .filter(|&at| goban[at].is_valid() && goban.at(at).is_none())
+        .filter(|&at| is_response(goban, at))
+        .filter(|&at| goban.is_legal(at, to_move) && !is_self_atari(goban, at, to_move))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::play_out;
+
+    fn only(policy: HeavyPolicy) -> HeavyPolicy {
+        policy.with_avoid_self_atari(0.0)
+    }
+
+    /// ```
+    /// . x . .
+    /// x o x .
+    /// . . . .
+    /// ```
+    #[test]
+    fn captures_stone_in_atari() {
+        let mut goban = Goban::new(4, 3);
+        for at in [(1u8, 0u8), (0, 1), (2, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((1u8, 1u8).into(), Color::White);
+
+        let policy = only(HeavyPolicy::new().with_capture(1.0).with_escape(0.0).with_pattern(0.0));
+
+        for seed in 0..10 {
+            let at = policy.select(&goban, Color::Black, Some((1u8, 1u8).into()), &mut Random::new(seed));
+
+            assert_eq!(at, Some((1u8, 2u8).into()));
+        }
+    }
+
+    /// ```
+    /// . x . .
+    /// x o x .
+    /// . . . .
+    /// ```
+    #[test]
+    fn escapes_from_atari() {
+        let mut goban = Goban::new(4, 3);
+        for at in [(1u8, 0u8), (0, 1), (2, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((1u8, 1u8).into(), Color::White);
+
+        let policy = only(HeavyPolicy::new().with_capture(0.0).with_escape(1.0).with_pattern(0.0));
+
+        for seed in 0..10 {
+            let at = policy.select(&goban, Color::White, Some((2u8, 1u8).into()), &mut Random::new(seed));
+
+            assert_eq!(at, Some((1u8, 2u8).into()));
+        }
+    }
+
+    /// ```
+    /// . . . . .
+    /// . x o . .
+    /// . . . . .
+    /// . . . . .
+    /// ```
+    #[test]
+    fn responds_with_pattern() {
+        let mut goban = Goban::new(5, 4);
+        goban.play((1u8, 1u8).into(), Color::Black);
+        goban.play((2u8, 1u8).into(), Color::White);
+
+        let policy = only(HeavyPolicy::new().with_capture(0.0).with_escape(0.0).with_pattern(1.0));
+
+        for seed in 0..10 {
+            let at = policy.select(&goban, Color::Black, Some((2u8, 1u8).into()), &mut Random::new(seed)).unwrap();
+
+            assert!(is_response(&goban, at));
+        }
+    }
+
+    /// ```
+    /// . x o .
+    /// x o . o
+    /// . . o .
+    /// ```
+    #[test]
+    fn detects_self_atari() {
+        let mut goban = Goban::new(4, 3);
+        for at in [(1u8, 0u8), (0, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+        for at in [(2u8, 0u8), (1, 1), (3, 1), (2, 2)] {
+            goban.play(at.into(), Color::White);
+        }
+
+        assert!(is_self_atari(&goban, (2u8, 1u8).into(), Color::Black));
+        assert!(!is_self_atari(&goban, (0u8, 0u8).into(), Color::White));
+        assert!(!is_self_atari(&goban, (0u8, 2u8).into(), Color::Black));
+    }
+
+    /// ```
+    /// . . o
+    /// o o o
+    /// o o o
+    /// ```
+    #[test]
+    fn fallback_move_is_uniform() {
+        let mut goban = Goban::new(3, 3);
+        for at in [(2u8, 0u8), (0, 1), (1, 1), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            goban.play(at.into(), Color::White);
+        }
+
+        let policy = HeavyPolicy::new().with_avoid_self_atari(1.0);
+        let mut rng = Random::new(0);
+        let num_first = (0..1000)
+            .filter(|_| policy.select(&goban, Color::Black, None, &mut rng) == Some((0u8, 0u8).into()))
+            .count();
+
+        assert!((400..600).contains(&num_first), "{}", num_first);
+    }
+
+    #[test]
+    fn heavy_play_out_is_reproducible() {
+        let mut a = Goban::new(7, 7);
+        let mut b = Goban::new(7, 7);
+        play_out(&mut a, Color::Black, None, 0, &HeavyPolicy::new(), &mut Random::new(11));
+        play_out(&mut b, Color::Black, None, 0, &HeavyPolicy::new(), &mut Random::new(11));
+
+        assert!(a == b);
+        assert!(a.iter().filter(|&at| a.at(at).is_some()).count() > 0);
+    }
+}
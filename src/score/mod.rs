@@ -1,5 +1,36 @@
+mod analysis;
+mod area;
+mod benson;
+mod chain;
+mod evaluator;
+mod game_state;
+mod heavy;
 mod normal;
+mod options;
+mod playout;
+mod prior;
+mod random;
+mod result;
+#[allow(clippy::module_inception)]
 mod score;
-mod search_tree;
+mod search;
+pub(crate) mod search_tree;
+mod seki;
+mod territory;
+mod transposition;
 
+pub use self::analysis::*;
+pub use self::area::*;
+pub use self::benson::*;
+pub use self::evaluator::*;
+pub use self::game_state::*;
+pub use self::heavy::*;
+pub use self::options::*;
+pub use self::playout::*;
+pub use self::prior::*;
+pub use self::random::*;
+pub use self::result::*;
 pub use self::score::*;
+pub use self::search::*;
+pub use self::seki::*;
+pub use self::territory::*;
@@ -0,0 +1,252 @@
+use super::{playout::LeafEvaluator, prior::Prior, search_tree::SearchTree};
+use std::time::Duration;
+
+/// The search budget and stopping criteria of `Score`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreOptions {
+    max_sims: u32,
+    time_limit: Option<Duration>,
+    confidence: f32,
+    min_visits: u32,
+    exploration: f32,
+    num_threads: usize,
+    evaluator: LeafEvaluator,
+    seed: u64,
+    rave: Option<f32>,
+    puct: Option<f32>,
+    prior: Prior,
+    transposition_table: Option<usize>,
+}
+
+impl Default for ScoreOptions {
+    fn default() -> Self {
+        Self {
+            max_sims: 32_000,
+            time_limit: None,
+            confidence: 0.51,
+            min_visits: 2,
+            exploration: SearchTree::EXPLORATION,
+            num_threads: 1,
+            evaluator: LeafEvaluator::default(),
+            seed: 0,
+            rave: None,
+            puct: None,
+            prior: Prior::default(),
+            transposition_table: None,
+        }
+    }
+}
+
+impl ScoreOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the maximum number of probes.
+    pub fn max_sims(&self) -> u32 {
+        self.max_sims
+    }
+
+    /// Set the maximum number of probes.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_sims` -
+    ///
+    pub fn with_max_sims(mut self, max_sims: u32) -> Self {
+        self.max_sims = max_sims;
+        self
+    }
+
+    /// Returns the maximum wall-clock time of the search, if any.
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Set the maximum wall-clock time of the search.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_limit` -
+    ///
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Returns the confidence threshold, the search stops early once the
+    /// standard deviation of every candidate is below `1 - confidence`.
+    pub fn confidence(&self) -> f32 {
+        self.confidence
+    }
+
+    /// Set the confidence threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `confidence` -
+    ///
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Returns the minimum number of visits every candidate must receive
+    /// before the search can stop early.
+    pub fn min_visits(&self) -> u32 {
+        self.min_visits
+    }
+
+    /// Set the minimum number of visits per candidate.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_visits` -
+    ///
+    pub fn with_min_visits(mut self, min_visits: u32) -> Self {
+        self.min_visits = min_visits;
+        self
+    }
+
+    /// Returns the exploration constant of UCB1.
+    pub fn exploration(&self) -> f32 {
+        self.exploration
+    }
+
+    /// Set the exploration constant of UCB1.
+    ///
+    /// # Arguments
+    ///
+    /// * `exploration` -
+    ///
+    pub fn with_exploration(mut self, exploration: f32) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Returns the number of threads that probe the search tree concurrently.
+    /// The search is deterministic when this is `1`, which is the default.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Set the number of threads that probe the search tree concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_threads` -
+    ///
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Returns how the leaves of the search tree are evaluated.
+    pub fn evaluator(&self) -> LeafEvaluator {
+        self.evaluator
+    }
+
+    /// Set how the leaves of the search tree are evaluated.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` -
+    ///
+    pub fn with_evaluator(mut self, evaluator: LeafEvaluator) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Returns the seed of the random number generators used by playouts.
+    /// Every thread uses its own generator, seeded with `seed + i`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Set the seed of the random number generators used by playouts.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` -
+    ///
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the equivalence parameter of Rapid Action Value Estimation
+    /// (RAVE), or `None` if it is disabled, which is the default.
+    pub fn rave(&self) -> Option<f32> {
+        self.rave
+    }
+
+    /// Mix the all-moves-as-first statistics of every candidate into the
+    /// selection, with the given equivalence parameter `equivalence`. The
+    /// higher it is, the more probes it takes before the plain mean of a
+    /// candidate dominates its all-moves-as-first mean.
+    ///
+    /// # Arguments
+    ///
+    /// * `equivalence` -
+    ///
+    pub fn with_rave(mut self, equivalence: f32) -> Self {
+        self.rave = Some(equivalence);
+        self
+    }
+
+    /// Returns the exploration constant of PUCT, or `None` if candidates are
+    /// selected using UCB1, which is the default.
+    pub fn puct(&self) -> Option<f32> {
+        self.puct
+    }
+
+    /// Select candidates using PUCT with the given exploration constant
+    /// `c_puct`, where the exploration of each candidate is weighted by its
+    /// prior.
+    ///
+    /// # Arguments
+    ///
+    /// * `c_puct` -
+    ///
+    pub fn with_puct(mut self, c_puct: f32) -> Self {
+        self.puct = Some(c_puct);
+        self
+    }
+
+    /// Returns the prior probabilities used by PUCT.
+    pub fn prior(&self) -> Prior {
+        self.prior
+    }
+
+    /// Set the prior probabilities used by PUCT.
+    ///
+    /// # Arguments
+    ///
+    /// * `prior` -
+    ///
+    pub fn with_prior(mut self, prior: Prior) -> Self {
+        self.prior = prior;
+        self
+    }
+
+    /// Returns the maximum number of entries in the transposition table, or
+    /// `None` if transpositions are searched separately.
+    pub fn transposition_table(&self) -> Option<usize> {
+        self.transposition_table
+    }
+
+    /// Share the statistics of positions that are reached through different
+    /// move orders, using a transposition table of at most `num_entries`
+    /// entries. The limit only bounds the table itself, the nodes are still
+    /// owned by the search tree, which grows with the number of probes
+    /// regardless of this limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_entries` -
+    ///
+    pub fn with_transposition_table(mut self, num_entries: usize) -> Self {
+        self.transposition_table = Some(num_entries);
+        self
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{Color, Goban, Point, pattern::{Eye, Pattern}};
+use super::{heavy::HeavyPolicy, random::Random};
+
+/// How the leaves of the search tree are evaluated.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LeafEvaluator {
+    /// Score the position as it is, where any neutral points are assumed to
+    /// be split evenly between the players.
+    #[default]
+    Score,
+
+    /// Play uniformly random moves until both players pass, and score the
+    /// final position.
+    Playout,
+
+    /// Play moves chosen by the given heavy policy until both players pass,
+    /// and score the final position.
+    HeavyPlayout(HeavyPolicy),
+}
+
+/// Chooses the moves played during a playout.
+pub trait PlayoutPolicy {
+    /// Returns the move to play for the given color `to_move`, or `None` to
+    /// pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `last_move` - the previous move, or `None` if it was a pass
+    /// * `rng` -
+    ///
+    fn select(&self, goban: &Goban, to_move: Color, last_move: Option<Point>, rng: &mut Random) -> Option<Point>;
+}
+
+/// A policy that plays uniformly random legal moves, except moves that fill
+/// one of its own eyes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UniformPolicy;
+
+impl PlayoutPolicy for UniformPolicy {
+    fn select(&self, goban: &Goban, to_move: Color, _last_move: Option<Point>, rng: &mut Random) -> Option<Point> {
+        random_move(goban, to_move, rng)
+    }
+}
+
+/// The number of random points `random_move` tries, before it falls back to
+/// listing every candidate move.
+const MAX_ATTEMPTS: usize = 8;
+
+/// Returns a uniformly random legal move for the given color `to_move`, that
+/// does not fill one of its own eyes, or `None` if there is no such move.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `to_move` -
+/// * `rng` -
+///
+pub fn random_move(goban: &Goban, to_move: Color, rng: &mut Random) -> Option<Point> {
+    random_move_where(goban, to_move, rng, |_| true)
+}
+
+/// Returns a uniformly random legal move for the given color `to_move`, that
+/// does not fill one of its own eyes and is accepted by `is_allowed`, or
+/// `None` if there is no such move.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `to_move` -
+/// * `rng` -
+/// * `is_allowed` -
+///
+pub(super) fn random_move_where(
+    goban: &Goban,
+    to_move: Color,
+    rng: &mut Random,
+    is_allowed: impl Fn(Point) -> bool,
+) -> Option<Point>
+{
+    let width = goban.width();
+    let size = width * goban.height();
+    let eyes = Eye::new().into_searcher(goban);
+    let point_at = |i: usize| Point::from(((i % width) as u8, (i / width) as u8));
+    let is_candidate = |at: Point| {
+        goban.at(at).is_none() && eyes.eye_at(at) != Some(to_move) && goban.is_legal(at, to_move) && is_allowed(at)
+    };
+
+    // rejection sampling is uniform, and usually succeeds quickly since most
+    // of the board is empty during a playout, otherwise pick uniformly among
+    // all of the candidates
+    for _ in 0..MAX_ATTEMPTS {
+        let at = point_at(rng.below(size));
+
+        if is_candidate(at) {
+            return Some(at);
+        }
+    }
+
+    let candidates = (0..size).map(point_at).filter(|&at| is_candidate(at)).collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates[rng.below(candidates.len())])
+    }
+}
+
+/// Play moves chosen by `policy` on `goban`, starting with the given color
+/// `to_move`, until both players pass in succession, or the game runs on for
+/// too long because of a long cycle. Returns the number of stones played.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `to_move` -
+/// * `last_move` - the move that led to this position, or `None` if it was a
+///   pass
+/// * `pass_count` - the number of consecutive passes before the playout
+/// * `policy` -
+/// * `rng` -
+///
+pub fn play_out(
+    goban: &mut Goban,
+    to_move: Color,
+    last_move: Option<Point>,
+    pass_count: u8,
+    policy: &impl PlayoutPolicy,
+    rng: &mut Random,
+) -> usize
+{
+    play_out_with(goban, to_move, last_move, pass_count, policy, rng, |_, _| {})
+}
+
+/// Play out the game like `play_out`, and call `on_move` with every stone
+/// that is played, in the order they are played.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `to_move` -
+/// * `last_move` -
+/// * `pass_count` -
+/// * `policy` -
+/// * `rng` -
+/// * `on_move` -
+///
+pub fn play_out_with(
+    goban: &mut Goban,
+    mut to_move: Color,
+    mut last_move: Option<Point>,
+    mut pass_count: u8,
+    policy: &impl PlayoutPolicy,
+    rng: &mut Random,
+    mut on_move: impl FnMut(Point, Color),
+) -> usize
+{
+    let max_moves = 3 * goban.width() * goban.height();
+    let mut num_moves = 0;
+
+    while pass_count < 2 && num_moves < max_moves {
+        last_move = policy.select(goban, to_move, last_move, rng);
+
+        match last_move {
+            Some(at) => {
+                goban.play(at, to_move);
+                on_move(at, to_move);
+                pass_count = 0;
+                num_moves += 1;
+            },
+            None => { pass_count += 1 },
+        }
+
+        to_move = to_move.opposite();
+    }
+
+    num_moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play_out_fills_board_except_eyes() {
+        let mut goban = Goban::new(7, 7);
+        let num_moves = play_out(&mut goban, Color::Black, None, 0, &UniformPolicy, &mut Random::new(1));
+        let eyes = Eye::new().into_searcher(&goban);
+
+        assert!(num_moves > 0);
+        for at in goban.iter().filter(|&at| goban.at(at).is_none()) {
+            assert!(eyes.eye_at(at).is_some() || !goban.is_legal(at, Color::Black) && !goban.is_legal(at, Color::White));
+        }
+    }
+
+    #[test]
+    fn play_out_is_reproducible() {
+        let mut a = Goban::new(5, 5);
+        let mut b = Goban::new(5, 5);
+        play_out(&mut a, Color::Black, None, 0, &UniformPolicy, &mut Random::new(3));
+        play_out(&mut b, Color::Black, None, 0, &UniformPolicy, &mut Random::new(3));
+
+        assert!(a == b);
+    }
+
+    /// ```
+    /// . x . x .
+    /// x x x x x
+    /// . . . . .
+    /// ```
+    #[test]
+    fn play_out_keeps_own_eyes() {
+        let mut goban = Goban::new(5, 3);
+        for at in [(1u8, 0u8), (3, 0), (0, 1), (1, 1), (2, 1), (3, 1), (4, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+
+        for seed in 0..10 {
+            let mut after = goban.clone();
+            play_out(&mut after, Color::Black, None, 0, &UniformPolicy, &mut Random::new(seed));
+
+            assert_eq!(after.at((2u8, 1u8).into()), Some(Color::Black));
+            assert_eq!(after.at((2u8, 0u8).into()), None);
+        }
+    }
+
+    #[test]
+    fn play_out_with_reports_every_stone() {
+        let mut goban = Goban::new(5, 5);
+        let mut moves = vec! [];
+        let num_moves = play_out_with(&mut goban, Color::Black, None, 0, &UniformPolicy, &mut Random::new(2), |at, color| {
+            moves.push((at, color));
+        });
+
+        assert_eq!(moves.len(), num_moves);
+        assert_eq!(moves.first().map(|&(_, color)| color), Some(Color::Black));
+    }
+
+    /// ```
+    /// . . o
+    /// o o o
+    /// o o o
+    /// ```
+    #[test]
+    fn random_move_is_uniform() {
+        let mut goban = Goban::new(3, 3);
+        for at in [(2u8, 0u8), (0, 1), (1, 1), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            goban.play(at.into(), Color::White);
+        }
+
+        let mut rng = Random::new(0);
+        let num_first = (0..1000)
+            .filter(|_| random_move(&goban, Color::Black, &mut rng) == Some((0u8, 0u8).into()))
+            .count();
+
+        assert!((400..600).contains(&num_first), "{}", num_first);
+    }
+
+    #[test]
+    fn two_passes_end_play_out() {
+        let mut goban = Goban::new(5, 5);
+
+        assert_eq!(play_out(&mut goban, Color::Black, None, 2, &UniformPolicy, &mut Random::new(0)), 0);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{Color, Goban, Point};
+
+/// Provides the prior probabilities of the candidate moves of a node, which
+/// guide PUCT selection towards the moves that are most likely to be good
+/// before they have been probed.
+pub trait PriorProvider: Send + Sync {
+    /// Returns a non-negative weight for every move in `moves`, where `None`
+    /// is a pass. The weights are normalized by the search, so they do not
+    /// need to sum to `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `last_move` - the move that led to this position, or `None` if it
+    ///   was a pass or is not known
+    /// * `moves` -
+    ///
+    fn priors(&self, goban: &Goban, to_move: Color, last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32>;
+}
+
+/// A prior that gives the same probability to every move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UniformPrior;
+
+impl PriorProvider for UniformPrior {
+    fn priors(&self, _goban: &Goban, _to_move: Color, _last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32> {
+        vec! [1.0; moves.len()]
+    }
+}
+
+/// A prior that prefers moves close to the previous move, moves that
+/// capture, and moves that put an opponent block in atari. Every move starts
+/// out with a weight of `1`, and the weight of each heuristic that applies is
+/// added to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeuristicPrior {
+    proximity: f32,
+    capture: f32,
+    atari: f32,
+}
+
+impl Default for HeuristicPrior {
+    fn default() -> Self {
+        Self {
+            proximity: 1.0,
+            capture: 4.0,
+            atari: 2.0,
+        }
+    }
+}
+
+impl PriorProvider for HeuristicPrior {
+    fn priors(&self, goban: &Goban, to_move: Color, last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32> {
+        moves.iter()
+            .map(|&at| {
+                let Some(at) = at else { return 1.0 };
+                let mut weight = 1.0;
+
+                if last_move.is_some_and(|last_move| last_move.x().abs_diff(at.x()) + last_move.y().abs_diff(at.y()) <= 2) {
+                    weight += self.proximity;
+                }
+
+                let opponents = at.neighbours()
+                    .filter(|&other| goban[other].is_valid() && goban.at(other) == Some(to_move.opposite()))
+                    .map(|other| goban.num_liberties_at(other))
+                    .collect::<Vec<_>>();
+
+                if opponents.contains(&1) {
+                    weight += self.capture;
+                }
+                if opponents.contains(&2) {
+                    weight += self.atari;
+                }
+
+                weight
+            })
+            .collect()
+    }
+}
+
+impl HeuristicPrior {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the weight added to moves within a distance of two of the
+    /// previous move.
+    pub fn proximity(&self) -> f32 {
+        self.proximity
+    }
+
+    /// Set the weight added to moves within a distance of two of the
+    /// previous move.
+    ///
+    /// # Arguments
+    ///
+    /// * `proximity` -
+    ///
+    pub fn with_proximity(mut self, proximity: f32) -> Self {
+        self.proximity = proximity;
+        self
+    }
+
+    /// Returns the weight added to moves that capture an opponent block.
+    pub fn capture(&self) -> f32 {
+        self.capture
+    }
+
+    /// Set the weight added to moves that capture an opponent block.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` -
+    ///
+    pub fn with_capture(mut self, capture: f32) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Returns the weight added to moves that put an opponent block in
+    /// atari.
+    pub fn atari(&self) -> f32 {
+        self.atari
+    }
+
+    /// Set the weight added to moves that put an opponent block in atari.
+    ///
+    /// # Arguments
+    ///
+    /// * `atari` -
+    ///
+    pub fn with_atari(mut self, atari: f32) -> Self {
+        self.atari = atari;
+        self
+    }
+}
+
+/// The built-in priors that can be selected through `ScoreOptions`. Other
+/// priors can be given directly to `Search::with_prior` or `Score::with_prior`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Prior {
+    /// Give the same probability to every move.
+    #[default]
+    Uniform,
+
+    /// Prefer moves according to the given heuristics.
+    Heuristic(HeuristicPrior),
+}
+
+impl PriorProvider for Prior {
+    fn priors(&self, goban: &Goban, to_move: Color, last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32> {
+        match self {
+            Self::Uniform => UniformPrior.priors(goban, to_move, last_move, moves),
+            Self::Heuristic(prior) => prior.priors(goban, to_move, last_move, moves),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_prior_is_equal() {
+        let goban = Goban::new(3, 3);
+        let moves = [Some((0u8, 0u8).into()), Some((1u8, 1u8).into()), None];
+
+        assert_eq!(UniformPrior.priors(&goban, Color::Black, None, &moves), vec! [1.0, 1.0, 1.0]);
+    }
+
+    /// ```
+    /// . x . . . .
+    /// x o x . . .
+    /// . . . . . .
+    /// . . . . . .
+    /// ```
+    #[test]
+    fn heuristic_prior_prefers_capture_and_proximity() {
+        let mut goban = Goban::new(6, 4);
+        for at in [(1u8, 0u8), (0, 1), (2, 1)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((1u8, 1u8).into(), Color::White);
+
+        let moves = [Some((1u8, 2u8).into()), Some((3u8, 1u8).into()), Some((5u8, 3u8).into()), None];
+        let priors = HeuristicPrior::new().priors(&goban, Color::Black, Some((2u8, 1u8).into()), &moves);
+
+        assert_eq!(priors, vec! [6.0, 2.0, 1.0, 1.0]);
+    }
+}
@@ -0,0 +1,78 @@
+/// A small and fast pseudo-random number generator (SplitMix64), so that
+/// playouts are reproducible given the same seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Returns a generator seeded with the given value `seed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` -
+    ///
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed integer in the range `0..n`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` -
+    ///
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Returns a uniformly distributed number in the range `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u32 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut a = Random::new(7);
+        let mut b = Random::new(7);
+        let mut c = Random::new(8);
+
+        let a = (0..8).map(|_| a.next_u64()).collect::<Vec<_>>();
+        let b = (0..8).map(|_| b.next_u64()).collect::<Vec<_>>();
+        let c = (0..8).map(|_| c.next_u64()).collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut random = Random::new(0);
+        let mut seen = [false; 5];
+
+        for _ in 0..100 {
+            let n = random.below(5);
+
+            assert!(n < 5);
+            seen[n] = true;
+        }
+
+        assert!(seen.iter().all(|&is_seen| is_seen));
+        assert!((0..100).all(|_| (0.0..1.0).contains(&random.next_f32())));
+    }
+}
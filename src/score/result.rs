@@ -0,0 +1,89 @@
+use crate::{Color, Point};
+use super::search_tree::{CandidateStatistics, SearchTree};
+
+/// The outcome of a `Score` search, which besides the most likely winner also
+/// describes how certain the search is about it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreResult {
+    pub(super) black_win_rate: f32,
+    pub(super) margin: f32,
+    pub(super) margin_variance: f32,
+    pub(super) best_move: Option<Point>,
+    pub(super) num_sims: u32,
+    pub(super) is_done: bool,
+}
+
+impl ScoreResult {
+    /// Returns the outcome of a search of the given root node `search_tree`.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_tree` -
+    /// * `to_move` -
+    /// * `margin` -
+    /// * `is_done` -
+    ///
+    pub(super) fn new(search_tree: &SearchTree, to_move: Color, margin: &CandidateStatistics, is_done: bool) -> Self {
+        let win_rate = search_tree.win_rate();
+
+        Self {
+            black_win_rate: if to_move == Color::Black { win_rate } else { 1.0 - win_rate },
+            margin: margin.mean(),
+            margin_variance: if margin.count() > 1.0 { margin.variance() } else { 0.0 },
+            best_move: search_tree.best_move(),
+            num_sims: search_tree.total_sims(),
+            is_done,
+        }
+    }
+
+    /// Returns the estimated probability that the given color `color` wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    ///
+    pub fn win_rate(&self, color: Color) -> f32 {
+        match color {
+            Color::Black => self.black_win_rate,
+            Color::White => 1.0 - self.black_win_rate,
+        }
+    }
+
+    /// Returns the color that is most likely to win.
+    pub fn winner(&self) -> Color {
+        if self.black_win_rate > 0.5 {
+            Color::Black
+        } else {
+            Color::White
+        }
+    }
+
+    /// Returns the expected number of points black is ahead by after komi,
+    /// averaged over all probes.
+    pub fn margin(&self) -> f32 {
+        self.margin
+    }
+
+    /// Returns the variance of the number of points black is ahead by, over
+    /// all probes.
+    pub fn margin_variance(&self) -> f32 {
+        self.margin_variance
+    }
+
+    /// Returns the best move for the player to move, or `None` if the best
+    /// move is to pass.
+    pub fn best_move(&self) -> Option<Point> {
+        self.best_move
+    }
+
+    /// Returns the number of probes performed by the search.
+    pub fn num_sims(&self) -> u32 {
+        self.num_sims
+    }
+
+    /// Returns whether the search stopped because it was confident in the
+    /// result, rather than because it ran out of simulations.
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+}
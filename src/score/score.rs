@@ -1,12 +1,32 @@
-use crate::{Goban, Color};
-use super::search_tree::SearchTree;
-use std::fmt::Debug;
+use crate::{Goban, Color, Point};
+use super::{
+    analysis::Analysis,
+    area::AreaScore,
+    benson::Benson,
+    evaluator::Evaluator,
+    game_state::GameState,
+    options::ScoreOptions,
+    prior::PriorProvider,
+    result::ScoreResult,
+    search::{new_root, run},
+    search_tree::{CandidateStatistics, SearchTree},
+    seki::Seki,
+    territory::{Territory, TerritoryScore},
+};
+use std::{fmt::Debug, sync::Arc};
 
 /// Determine the final score of the given game using a small Monte Carlo Tree
 /// Search (MCTS).
 pub struct Score<'a> {
     goban: &'a Goban,
+    to_move: Color,
+    komi: f32,
     search_tree: SearchTree,
+    ownership: Vec<f32>,
+    margin: CandidateStatistics,
+    is_done: bool,
+    black_captures: usize,
+    white_captures: usize,
 }
 
 impl<'a> Debug for Score<'a> {
@@ -18,50 +38,411 @@ impl<'a> Debug for Score<'a> {
 
 impl<'a> Score<'a> {
     pub fn new(goban: &'a Goban, to_move: Color, komi: f32) -> Self {
-        let mut search_tree = SearchTree::new(goban, to_move, 0);
-        loop {
-            search_tree.probe(goban.clone(), komi);
+        Self::with_options(goban, to_move, komi, ScoreOptions::default())
+    }
 
-            if search_tree.total_sims() > 32_000 || search_tree.is_done(0.51) {
-                break
-            }
+    /// Returns the score of the given position `goban`, searching until one
+    /// of the stopping criteria in `options` is met.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `komi` -
+    /// * `options` -
+    ///
+    pub fn with_options(goban: &'a Goban, to_move: Color, komi: f32, options: ScoreOptions) -> Self {
+        Self::search(goban, to_move, 0, komi, options, Arc::new(options.evaluator()), Arc::new(options.prior()))
+    }
+
+    /// Returns the score of the given position `goban`, searching until one
+    /// of the stopping criteria in `options` is met, and evaluating the
+    /// leaves using `evaluator` instead of the evaluator in the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `komi` -
+    /// * `options` -
+    /// * `evaluator` -
+    ///
+    pub fn with_evaluator(goban: &'a Goban, to_move: Color, komi: f32, options: ScoreOptions, evaluator: Arc<dyn Evaluator>) -> Self {
+        Self::search(goban, to_move, 0, komi, options, evaluator, Arc::new(options.prior()))
+    }
+
+    /// Returns the score of the given position `goban`, searching until one
+    /// of the stopping criteria in `options` is met, and weighting the
+    /// candidates using `prior` instead of the prior in the options if PUCT
+    /// is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `komi` -
+    /// * `options` -
+    /// * `prior` -
+    ///
+    pub fn with_prior(goban: &'a Goban, to_move: Color, komi: f32, options: ScoreOptions, prior: Arc<dyn PriorProvider>) -> Self {
+        Self::search(goban, to_move, 0, komi, options, Arc::new(options.evaluator()), prior)
+    }
+
+    /// Returns the score of the given game state `state`, taking the previous
+    /// passes, the ko history, and the prisoners of the game into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` -
+    /// * `options` -
+    ///
+    pub fn from_state(state: &'a GameState, options: ScoreOptions) -> Self {
+        let pass_count = state.pass_count().min(2) as u8;
+        let mut score = Self::search(state.goban(), state.to_move(), pass_count, state.komi(), options, Arc::new(options.evaluator()), Arc::new(options.prior()));
+        score.black_captures = state.captures(Color::Black);
+        score.white_captures = state.captures(Color::White);
+        score
+    }
+
+    fn search(
+        goban: &'a Goban,
+        to_move: Color,
+        pass_count: u8,
+        komi: f32,
+        options: ScoreOptions,
+        evaluator: Arc<dyn Evaluator>,
+        prior: Arc<dyn PriorProvider>,
+    ) -> Self
+    {
+        let search_tree = new_root(goban, to_move, pass_count, options, evaluator, prior);
+        let mut ownership = vec! [0.0; goban.width() * goban.height()];
+        let mut margin = CandidateStatistics::new();
+        let is_done = run(&search_tree, goban, komi, options, &mut ownership, &mut margin);
+        let total_sims = search_tree.total_sims() as f32;
+        for value in ownership.iter_mut() {
+            *value /= total_sims;
         }
 
-        Self { goban, search_tree }
+        Self {
+            goban,
+            to_move,
+            komi,
+            search_tree,
+            ownership,
+            margin,
+            is_done,
+            black_captures: 0,
+            white_captures: 0,
+        }
+    }
+
+    /// Returns the outcome of the search, including how certain it is.
+    pub fn result(&self) -> ScoreResult {
+        ScoreResult::new(&self.search_tree, self.to_move, &self.margin, self.is_done)
     }
 
     pub fn winner(&self) -> Color {
         self.search_tree.winner()
     }
+
+    /// Returns the statistics of every candidate move of the current position,
+    /// from which the rest of the search tree can be walked.
+    pub fn analysis(&self) -> Analysis {
+        self.search_tree.analysis()
+    }
+
+    /// Returns the best move for the player to move, or `None` if the best
+    /// move is to pass.
+    pub fn best_move(&self) -> Option<Point> {
+        self.search_tree.best_move()
+    }
+
+    /// Returns the number of points black is ahead by after komi, according
+    /// to the Tromp-Taylor area score of the position. Dead stones are not
+    /// removed.
+    pub fn score_lead(&self) -> f32 {
+        AreaScore::new(self.goban, self.komi).margin()
+    }
+
+    /// Returns the ownership of every point, in the same order as
+    /// `Goban::iter`, where `1` means the point always ended up belonging to
+    /// black, and `-1` that it always ended up belonging to white.
+    pub fn ownership(&self) -> &[f32] {
+        &self.ownership
+    }
+
+    /// Returns the ownership of the given point `at`, in the range `[-1, 1]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn ownership_at(&self, at: Point) -> f32 {
+        self.ownership[(at.y() - 1) * self.goban.width() + (at.x() - 1)]
+    }
+
+    /// Returns the chains that are alive in seki.
+    pub fn seki(&self) -> Seki {
+        Seki::new(self.goban)
+    }
+
+    /// Returns the stones that are more likely than not to end up belonging
+    /// to the opponent, excluding stones in seki. Stones that are
+    /// unconditionally alive are never dead, and stones inside the vital
+    /// regions of the opponent are always dead, regardless of the search.
+    pub fn dead_stones(&self) -> Vec<Point> {
+        let seki = self.seki();
+        let black = Benson::new(self.goban, Color::Black);
+        let white = Benson::new(self.goban, Color::White);
+
+        self.goban.iter()
+            .zip(self.ownership.iter())
+            .filter(|&(at, _)| !seki.contains(at))
+            .filter(|&(at, &value)| match self.goban.at(at) {
+                Some(Color::Black) => !black.is_alive(at) && (white.is_vital(at) || value < 0.0),
+                Some(Color::White) => !white.is_alive(at) && (black.is_vital(at) || value > 0.0),
+                None => false,
+            })
+            .map(|(at, _)| at)
+            .collect()
+    }
+
+    /// Returns the area score of the position, after the stones in
+    /// `dead_stones` have been removed.
+    pub fn final_area(&self) -> AreaScore {
+        let mut goban = self.goban.clone();
+        for at in self.dead_stones() {
+            let _ = goban.setup(at, None);
+        }
+
+        AreaScore::new(&goban, self.komi)
+    }
+
+    /// Returns the territory score of the position, after the stones in
+    /// `dead_stones` have been removed, and without territory in seki. The
+    /// prisoners are only counted if the score was created using
+    /// `from_state`.
+    pub fn final_territory(&self) -> TerritoryScore {
+        Territory::new(self.goban, self.komi)
+            .captures(Color::Black, self.black_captures)
+            .captures(Color::White, self.white_captures)
+            .dead_stones(self.dead_stones())
+            .seki(self.seki().stones().iter().copied())
+            .score()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::score::{Evaluation, Leaf, LeafEvaluator, Random};
+    use std::sync::atomic::{AtomicU32, Ordering};
 
     /// ```
     /// . x x
     /// x . x
     /// x o x
     /// ```
+    fn goban_3x3() -> Goban {
+        let mut goban = Goban::new(3, 3);
+        for at in [(0u8, 0u8), (0, 2), (1, 0), (1, 2), (2, 1), (2, 2)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((0u8, 1u8).into(), Color::White);
+
+        goban
+    }
+
     #[test]
     fn black_wins_3x3() {
+        let goban = goban_3x3();
+
+        for _ in 0..10 {
+            let score = Score::new(&goban, Color::Black, 0.5);
+
+            assert_eq!(score.winner(), Color::Black, "{:?}", score);
+        }
+    }
+
+    /// ```
+    /// x x .
+    /// o x x
+    /// . x .
+    /// ```
+    #[test]
+    fn captured_stone_is_dead() {
         let mut goban = Goban::new(3, 3);
         goban.play((0u8, 0u8).into(), Color::Black);
-        goban.play((0u8, 2u8).into(), Color::Black);
         goban.play((1u8, 0u8).into(), Color::Black);
-        goban.play((1u8, 2u8).into(), Color::Black);
+        goban.play((1u8, 1u8).into(), Color::Black);
         goban.play((2u8, 1u8).into(), Color::Black);
-        goban.play((2u8, 2u8).into(), Color::Black);
+        goban.play((1u8, 2u8).into(), Color::Black);
         goban.play((0u8, 1u8).into(), Color::White);
 
-        for _ in 0..10 {
-            let score = Score::new(&goban, Color::Black, 0.5);
+        let score = Score::new(&goban, Color::Black, 6.5);
 
-            assert_eq!(score.winner(), Color::Black, "{:?}", score);
+        assert_eq!(score.ownership().len(), 9);
+        assert!(score.ownership().iter().all(|value| (-1.0..=1.0).contains(value)));
+        assert!(score.ownership_at((1u8, 1u8).into()) > 0.9, "{:?}", score.ownership());
+        assert_eq!(score.dead_stones(), vec! [(0u8, 1u8).into()], "{:?}", score.ownership());
+
+        let result = score.result();
+        assert_eq!(result.winner(), Color::Black, "{:?}", score);
+        assert!(result.win_rate(Color::Black) > 0.5 && result.win_rate(Color::White) < 0.5);
+        assert!(result.is_done());
+        assert_eq!(result.num_sims(), score.search_tree.total_sims());
+        assert!(result.margin() > 0.0 && result.margin_variance() >= 0.0, "{:?}", result);
+
+        let territory = score.final_territory();
+        assert_eq!(territory.territory_at((0u8, 1u8).into()), Some(Color::Black));
+        assert_eq!(territory.black().dead_stones(), 1);
+
+        let area = score.final_area();
+        assert_eq!(area.owner_at((0u8, 1u8).into()), Some(Color::Black));
+        assert_eq!((area.black(), area.white()), (9, 0));
+        assert_eq!(area.result(), "B+2.5");
+    }
+
+    #[test]
+    fn threads_share_search_budget() {
+        let goban = goban_3x3();
+        let options = ScoreOptions::new().with_num_threads(4).with_max_sims(200).with_min_visits(1_000);
+        let score = Score::with_options(&goban, Color::Black, 0.5, options);
+
+        assert_eq!(score.result().num_sims(), 200);
+        assert_eq!(score.analysis().moves().iter().map(|mv| mv.visits()).sum::<u32>(), 200);
+    }
+
+    #[test]
+    fn one_thread_is_deterministic() {
+        let goban = Goban::new(5, 5);
+        let options = ScoreOptions::new()
+            .with_max_sims(500)
+            .with_min_visits(1_000)
+            .with_evaluator(LeafEvaluator::Playout);
+        let a = Score::with_options(&goban, Color::Black, 7.5, options);
+        let b = Score::with_options(&goban, Color::Black, 7.5, options);
+
+        assert_eq!(a.result(), b.result());
+        assert_eq!(a.ownership(), b.ownership());
+    }
+
+    /// Push every node reachable from `search_tree` onto `nodes`, once for
+    /// every path to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_tree` -
+    /// * `nodes` -
+    ///
+    fn collect_nodes(search_tree: &SearchTree, nodes: &mut Vec<Arc<SearchTree>>) {
+        for mv in search_tree.analysis().moves() {
+            if let Some(child) = search_tree.child(mv.at()) {
+                collect_nodes(&child, nodes);
+                nodes.push(child);
+            }
+        }
+    }
+
+    #[test]
+    fn transpositions_share_nodes() {
+        let goban = Goban::new(3, 3);
+        let options = ScoreOptions::new()
+            .with_max_sims(2_000)
+            .with_min_visits(100_000)
+            .with_transposition_table(4_096);
+        let score = Score::with_options(&goban, Color::Black, 0.5, options);
+        let mut nodes = vec! [];
+        collect_nodes(&score.search_tree, &mut nodes);
+
+        let num_shared = nodes.iter()
+            .enumerate()
+            .filter(|&(i, node)| nodes[..i].iter().any(|other| Arc::ptr_eq(node, other)))
+            .count();
+
+        assert!(num_shared > 0);
+    }
+
+    /// An evaluator that scores the position, and counts the leaves it is
+    /// given.
+    struct CountLeaves(AtomicU32);
+
+    impl Evaluator for CountLeaves {
+        fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+            self.0.fetch_add(1, Ordering::Relaxed);
+
+            LeafEvaluator::Score.evaluate(leaf, rng)
+        }
+    }
+
+    #[test]
+    fn custom_evaluator_is_used() {
+        let goban = goban_3x3();
+        let options = ScoreOptions::new().with_max_sims(200).with_min_visits(1_000);
+        let evaluator = Arc::new(CountLeaves(AtomicU32::new(0)));
+        let score = Score::with_evaluator(&goban, Color::Black, 0.5, options, evaluator.clone());
+
+        assert_eq!(score.winner(), Color::Black, "{:?}", score);
+        assert_eq!(evaluator.0.load(Ordering::Relaxed), 200);
+    }
+
+    /// A prior that prefers to pass.
+    struct PassPrior;
+
+    impl PriorProvider for PassPrior {
+        fn priors(&self, _goban: &Goban, _to_move: Color, _last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32> {
+            moves.iter().map(|at| if at.is_none() { 4.0 } else { 1.0 }).collect()
         }
     }
 
+    #[test]
+    fn custom_prior_is_used() {
+        let goban = Goban::new(3, 3);
+        let options = ScoreOptions::new().with_puct(1.5).with_max_sims(10).with_min_visits(1_000);
+        let score = Score::with_prior(&goban, Color::Black, 0.5, options, Arc::new(PassPrior));
+        let analysis = score.analysis();
+        let pass = analysis.get(None).unwrap();
+        let other = analysis.get(Some((1u8, 1u8).into())).unwrap();
+
+        assert_eq!(pass.prior(), 4.0 * other.prior());
+    }
+
+    /// ```
+    /// x .
+    /// ```
+    #[test]
+    fn previous_pass_ends_game() {
+        let mut goban = Goban::new(2, 1);
+        goban.play((0u8, 0u8).into(), Color::Black);
+
+        let options = ScoreOptions::new().with_min_visits(100);
+        let score = Score::with_options(&goban, Color::Black, 0.5, options);
+        assert_eq!(score.winner(), Color::White, "{:?}", score);
+
+        let state = GameState::new(goban, Color::Black).with_komi(0.5).with_pass_count(1);
+        let score = Score::from_state(&state, options);
+        assert_eq!(score.winner(), Color::Black, "{:?}", score);
+        assert_eq!(score.best_move(), None);
+    }
+
+    #[test]
+    fn options_limit_search() {
+        let goban = Goban::new(9, 9);
+        let options = ScoreOptions::new()
+            .with_max_sims(100)
+            .with_min_visits(10)
+            .with_exploration(1.0);
+        let result = Score::with_options(&goban, Color::Black, 7.5, options).result();
+
+        assert_eq!(result.num_sims(), 100);
+        assert!(!result.is_done());
+
+        let options = ScoreOptions::new().with_time_limit(std::time::Duration::from_millis(10));
+        let result = Score::with_options(&goban, Color::Black, 7.5, options).result();
+
+        assert!(result.num_sims() < 32_000);
+    }
+
     /// ```
     /// o x x . x . x . .
     /// o x . x o x x x x
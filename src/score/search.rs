@@ -0,0 +1,484 @@
+use crate::{Color, Goban, Illegal, Point};
+use super::{
+    analysis::Analysis,
+    evaluator::Evaluator,
+    game_state::GameState,
+    options::ScoreOptions,
+    prior::PriorProvider,
+    random::Random,
+    result::ScoreResult,
+    search_tree::{CandidateStatistics, SearchTree},
+    transposition::TranspositionTable,
+};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex, PoisonError, atomic::{AtomicBool, AtomicU32, Ordering}},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Returns a new root node for the given position `goban`, configured
+/// according to `options`, whose leaves are evaluated by `evaluator`, and
+/// whose candidates are weighted by `prior` if PUCT is enabled.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `to_move` -
+/// * `pass_count` -
+/// * `options` -
+/// * `evaluator` -
+/// * `prior` -
+///
+pub(super) fn new_root(
+    goban: &Goban,
+    to_move: Color,
+    pass_count: u8,
+    options: ScoreOptions,
+    evaluator: Arc<dyn Evaluator>,
+    prior: Arc<dyn PriorProvider>,
+) -> SearchTree
+{
+    let search_tree = SearchTree::new(goban, to_move, pass_count)
+        .with_exploration(options.exploration())
+        .with_evaluator(evaluator);
+    let search_tree = match options.rave() {
+        Some(equivalence) => search_tree.with_rave(equivalence),
+        None => search_tree,
+    };
+    let search_tree = match options.puct() {
+        Some(c_puct) => search_tree.with_puct(c_puct).with_prior(prior, goban, None),
+        None => search_tree,
+    };
+
+    match options.transposition_table() {
+        Some(num_entries) => search_tree.with_transpositions(Arc::new(TranspositionTable::new(num_entries))),
+        None => search_tree,
+    }
+}
+
+/// Probe the given node `search_tree` for the position `goban` until one of
+/// the stopping criteria in `options` is met, and add the ownership and
+/// margin of every probe to `ownership` and `margin`. Returns whether the
+/// search stopped because it was confident in the result.
+///
+/// # Arguments
+///
+/// * `search_tree` -
+/// * `goban` -
+/// * `komi` -
+/// * `options` -
+/// * `ownership` -
+/// * `margin` -
+///
+pub(super) fn run(
+    search_tree: &SearchTree,
+    goban: &Goban,
+    komi: f32,
+    options: ScoreOptions,
+    ownership: &mut [f32],
+    margin: &mut CandidateStatistics,
+) -> bool {
+    let started_at = Instant::now();
+    let num_started = AtomicU32::new(0);
+    let is_stopped = AtomicBool::new(false);
+    let is_done = AtomicBool::new(false);
+    let totals = Mutex::new((ownership, margin));
+
+    thread::scope(|scope| {
+        for i in 0..options.num_threads() {
+            let (num_started, is_stopped, is_done, totals) = (&num_started, &is_stopped, &is_done, &totals);
+
+            scope.spawn(move || {
+                let mut rng = Random::new(options.seed().wrapping_add(i as u64));
+
+                while !is_stopped.load(Ordering::Relaxed) && num_started.fetch_add(1, Ordering::Relaxed) < options.max_sims().max(1) {
+                    let result = search_tree.probe(goban.clone(), komi, &mut rng);
+                    let mut totals = totals.lock().unwrap_or_else(PoisonError::into_inner);
+                    let (ownership, margin) = &mut *totals;
+                    margin.update(result.margin());
+
+                    for (total, value) in ownership.iter_mut().zip(result.ownership().unwrap_or_default()) {
+                        *total += value;
+                    }
+
+                    drop(totals);
+
+                    if search_tree.is_done(options.confidence(), options.min_visits()) {
+                        is_done.store(true, Ordering::Relaxed);
+                        is_stopped.store(true, Ordering::Relaxed);
+                    } else if options.time_limit().is_some_and(|time_limit| started_at.elapsed() >= time_limit) {
+                        is_stopped.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    is_done.into_inner()
+}
+
+/// A search that owns its game state, so that it can be kept between moves.
+/// After a move is played the root of the search tree advances to the
+/// subtree of that move, keeping its simulations, while the rest of the tree
+/// is dropped.
+pub struct Search {
+    state: GameState,
+    options: ScoreOptions,
+    evaluator: Arc<dyn Evaluator>,
+    prior: Arc<dyn PriorProvider>,
+    search_tree: Arc<SearchTree>,
+    ownership: Vec<f32>,
+    margin: CandidateStatistics,
+    is_done: bool,
+}
+
+impl Debug for Search {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "{:?}", self.search_tree)?;
+        writeln!(f, "{}", self.search_tree.as_sgf(self.state.goban()))
+    }
+}
+
+impl Search {
+    /// Returns a search of the given game state `state` that has not been
+    /// probed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` -
+    /// * `options` -
+    ///
+    pub fn new(state: GameState, options: ScoreOptions) -> Self {
+        let evaluator: Arc<dyn Evaluator> = Arc::new(options.evaluator());
+        let prior: Arc<dyn PriorProvider> = Arc::new(options.prior());
+        let search_tree = new_root(state.goban(), state.to_move(), state.pass_count().min(2) as u8, options, evaluator.clone(), prior.clone());
+        let ownership = vec! [0.0; state.goban().width() * state.goban().height()];
+
+        Self {
+            state,
+            options,
+            evaluator,
+            prior,
+            search_tree: Arc::new(search_tree),
+            ownership,
+            margin: CandidateStatistics::new(),
+            is_done: false,
+        }
+    }
+
+    /// Evaluate the leaves using `evaluator` instead of the evaluator in the
+    /// options. This discards any previous simulations.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` -
+    ///
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn Evaluator>) -> Self {
+        self.evaluator = evaluator;
+        self.restart();
+        self
+    }
+
+    /// Weight the candidates using `prior` instead of the prior in the
+    /// options, which only has an effect if PUCT is enabled. This discards
+    /// any previous simulations.
+    ///
+    /// # Arguments
+    ///
+    /// * `prior` -
+    ///
+    pub fn with_prior(mut self, prior: Arc<dyn PriorProvider>) -> Self {
+        self.prior = prior;
+        self.restart();
+        self
+    }
+
+    /// Returns a new root node for the current position.
+    fn new_root(&self) -> Arc<SearchTree> {
+        let pass_count = self.state.pass_count().min(2) as u8;
+
+        Arc::new(new_root(self.state.goban(), self.state.to_move(), pass_count, self.options, self.evaluator.clone(), self.prior.clone()))
+    }
+
+    /// Discard every simulation, and start over from a new root node.
+    fn restart(&mut self) {
+        self.search_tree = self.new_root();
+        self.ownership.fill(0.0);
+        self.margin = CandidateStatistics::new();
+        self.is_done = false;
+    }
+
+    /// Returns the current game state.
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn options(&self) -> ScoreOptions {
+        self.options
+    }
+
+    /// Continue searching the current position until one of the stopping
+    /// criteria in the options is met, where the maximum number of probes
+    /// and the time limit apply to this call only. Returns the outcome of
+    /// the search so far.
+    ///
+    /// The seed of the options is advanced by the number of probes so far,
+    /// so that a continued search does not repeat the random numbers of the
+    /// previous calls.
+    pub fn run(&mut self) -> ScoreResult {
+        self.run_with(self.options)
+    }
+
+    /// Continue searching the current position like `run`, but stop after
+    /// the given time limit `time_limit` instead of the time limit in the
+    /// options, e.g. when the time available for a move changes during the
+    /// game.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_limit` -
+    ///
+    pub fn run_with_time_limit(&mut self, time_limit: Duration) -> ScoreResult {
+        self.run_with(self.options.with_time_limit(time_limit))
+    }
+
+    /// Continue searching the current position for the given duration
+    /// `duration`, ignoring the maximum number of probes and the confidence
+    /// threshold of the options, e.g. for one slice of an ongoing analysis.
+    /// Returns the outcome of the search so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` -
+    ///
+    pub fn run_for(&mut self, duration: Duration) -> ScoreResult {
+        let options = self.options
+            .with_max_sims(u32::MAX)
+            .with_confidence(1.0)
+            .with_time_limit(duration);
+
+        self.run_with(options)
+    }
+
+    fn run_with(&mut self, options: ScoreOptions) -> ScoreResult {
+        let options = options.with_seed(options.seed().wrapping_add(self.total_sims() as u64));
+
+        self.is_done = run(
+            &self.search_tree,
+            self.state.goban(),
+            self.state.komi(),
+            options,
+            &mut self.ownership,
+            &mut self.margin,
+        );
+
+        self.result()
+    }
+
+    /// Play the given move `at` for the player to move, where `None` is a
+    /// pass. The subtree of the move becomes the new root, or a new root is
+    /// created if the move has not been expanded yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn play(&mut self, at: Option<Point>) -> Result<(), Illegal> {
+        self.state.play(at)?;
+
+        self.search_tree = match self.search_tree.child(at) {
+            Some(child) => child,
+            None => self.new_root(),
+        };
+        self.ownership.fill(0.0);
+        self.margin = CandidateStatistics::new();
+        self.is_done = false;
+
+        Ok(())
+    }
+
+    /// Returns the outcome of the search of the current position, including
+    /// the simulations that were kept from before the last move.
+    pub fn result(&self) -> ScoreResult {
+        ScoreResult::new(&self.search_tree, self.state.to_move(), &self.margin, self.is_done)
+    }
+
+    /// Returns the statistics of every candidate move of the current position,
+    /// from which the rest of the search tree can be walked.
+    pub fn analysis(&self) -> Analysis {
+        self.search_tree.analysis()
+    }
+
+    /// Returns the best move for the player to move, or `None` if the best
+    /// move is to pass.
+    pub fn best_move(&self) -> Option<Point> {
+        self.search_tree.best_move()
+    }
+
+    /// Returns the number of probes of the current position, including the
+    /// probes that were kept from before the last move.
+    pub fn total_sims(&self) -> u32 {
+        self.search_tree.total_sims()
+    }
+
+    /// Returns the ownership of every point, in the same order as
+    /// `Goban::iter`, averaged over the probes since the last move.
+    pub fn ownership(&self) -> Vec<f32> {
+        let count = self.margin.count().max(1.0);
+
+        self.ownership.iter().map(|value| value / count).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::{BatchEvaluator, Evaluation, Leaf, LeafEvaluator, Random};
+
+    /// An evaluator that scores the position, and prefers the first point
+    /// of the board in its policy.
+    struct FirstPoint;
+
+    impl Evaluator for FirstPoint {
+        fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+            let size = leaf.goban().width() * leaf.goban().height();
+            let mut policy = vec! [1.0; size + 1];
+            policy[0] = 2.0;
+
+            LeafEvaluator::Score.evaluate(leaf, rng).with_policy(policy)
+        }
+    }
+
+    /// An evaluator that scores the position, and counts the batches it is
+    /// given.
+    struct CountBatches(AtomicU32);
+
+    impl Evaluator for CountBatches {
+        fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+            LeafEvaluator::Score.evaluate(leaf, rng)
+        }
+
+        fn evaluate_batch(&self, leaves: &[Leaf], rng: &mut Random) -> Vec<Evaluation> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+
+            leaves.iter().map(|leaf| self.evaluate(leaf, rng)).collect()
+        }
+    }
+
+    #[test]
+    fn play_keeps_subtree() {
+        let options = ScoreOptions::new().with_max_sims(500).with_min_visits(1_000);
+        let mut search = Search::new(GameState::new(Goban::new(3, 3), Color::Black).with_komi(0.5), options);
+        let result = search.run();
+        assert_eq!(result.num_sims(), 500);
+
+        let at = search.best_move();
+        let kept = search.search_tree.child(at).unwrap().total_sims();
+        assert!(kept > 0);
+
+        search.play(at).unwrap();
+        assert_eq!(search.state().to_move(), Color::White);
+        assert_eq!(search.total_sims(), kept);
+
+        let result = search.run();
+        assert_eq!(result.num_sims(), kept + 500);
+    }
+
+    #[test]
+    fn batched_evaluator_with_threads() {
+        let options = ScoreOptions::new().with_num_threads(4).with_max_sims(200).with_min_visits(1_000);
+        let evaluator = Arc::new(BatchEvaluator::new(CountBatches(AtomicU32::new(0)), 4));
+        let mut search = Search::new(GameState::new(Goban::new(5, 5), Color::Black), options)
+            .with_evaluator(evaluator.clone());
+        let result = search.run();
+        let num_batches = evaluator.inner().0.load(Ordering::Relaxed);
+
+        assert_eq!(result.num_sims(), 200);
+        assert!(num_batches > 0 && num_batches < 200, "{}", num_batches);
+    }
+
+    #[test]
+    fn policy_is_used_as_prior() {
+        let options = ScoreOptions::new().with_puct(1.5).with_max_sims(500).with_min_visits(1_000);
+        let mut search = Search::new(GameState::new(Goban::new(3, 3), Color::Black), options)
+            .with_evaluator(Arc::new(FirstPoint));
+        search.run();
+
+        let children = search.analysis().best().and_then(|best| best.children()).unwrap();
+        let first = children.get(Some((0u8, 0u8).into())).unwrap();
+        let other = children.moves().iter().find(|mv| mv.at() != Some((0u8, 0u8).into())).unwrap();
+
+        assert_eq!(first.prior(), 2.0 * other.prior());
+    }
+
+    /// A prior that prefers the first point of the board.
+    struct FirstPointPrior;
+
+    impl PriorProvider for FirstPointPrior {
+        fn priors(&self, _goban: &Goban, _to_move: Color, _last_move: Option<Point>, moves: &[Option<Point>]) -> Vec<f32> {
+            moves.iter().map(|&at| if at == Some((0u8, 0u8).into()) { 2.0 } else { 1.0 }).collect()
+        }
+    }
+
+    #[test]
+    fn custom_prior_is_used() {
+        let options = ScoreOptions::new().with_puct(1.5).with_max_sims(10).with_min_visits(1_000);
+        let mut search = Search::new(GameState::new(Goban::new(3, 3), Color::Black), options)
+            .with_prior(Arc::new(FirstPointPrior));
+        search.run();
+
+        let analysis = search.analysis();
+        let first = analysis.get(Some((0u8, 0u8).into())).unwrap();
+        let other = analysis.moves().iter().find(|mv| mv.at() != Some((0u8, 0u8).into())).unwrap();
+
+        assert_eq!(first.prior(), 2.0 * other.prior());
+    }
+
+    /// An evaluator that counts the finished games it is given.
+    struct CountFinished(AtomicU32);
+
+    impl Evaluator for CountFinished {
+        fn evaluate(&self, leaf: &Leaf, rng: &mut Random) -> Evaluation {
+            if leaf.pass_count() >= 2 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+
+            LeafEvaluator::Score.evaluate(leaf, rng)
+        }
+    }
+
+    #[test]
+    fn finished_games_are_evaluated() {
+        let evaluator = Arc::new(CountFinished(AtomicU32::new(0)));
+        let options = ScoreOptions::new().with_max_sims(100).with_min_visits(1_000);
+        let state = GameState::new(Goban::new(3, 3), Color::Black).with_pass_count(1);
+        let mut search = Search::new(state, options).with_evaluator(evaluator.clone());
+        search.run();
+
+        assert!(evaluator.0.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn play_unexpanded_move_starts_new_root() {
+        let options = ScoreOptions::new().with_max_sims(10);
+        let mut search = Search::new(GameState::new(Goban::new(3, 3), Color::Black), options);
+
+        assert_eq!(search.play(None), Ok(()));
+        assert_eq!(search.total_sims(), 0);
+        assert_eq!(search.state().pass_count(), 1);
+        assert_eq!(search.play(Some((1u8, 1u8).into())), Ok(()));
+        assert_eq!(search.play(Some((1u8, 1u8).into())), Err(Illegal::Occupied));
+        assert_eq!(search.state().to_move(), Color::Black);
+    }
+
+    #[test]
+    fn run_for_ignores_search_budget() {
+        let options = ScoreOptions::new().with_max_sims(10);
+        let mut search = Search::new(GameState::new(Goban::new(5, 5), Color::Black), options);
+        let result = search.run_for(Duration::from_millis(50));
+
+        assert!(result.num_sims() > 10, "{:?}", result);
+        assert_eq!(search.ownership().len(), 25);
+    }
+}
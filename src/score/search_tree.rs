@@ -1,5 +1,21 @@
-use crate::{Color, Goban, Point, score::normal::Normal};
-use std::{fmt::{Debug, Display}, cmp::Ordering};
+use crate::{
+    Color, Goban, Point,
+    score::{
+        analysis::{Analysis, MoveAnalysis},
+        benson::Benson,
+        evaluator::{Evaluation, Evaluator, Leaf},
+        playout::LeafEvaluator,
+        prior::PriorProvider,
+        random::Random,
+        transposition::{Key, TranspositionTable},
+    },
+};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    cmp::Ordering,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, atomic::{self, AtomicU32}},
+};
 
 #[derive(PartialEq)]
 struct OrderedFloat(f32);
@@ -12,19 +28,19 @@ impl Display for OrderedFloat {
 
 impl PartialOrd for OrderedFloat {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.0 > other.0 {
-            Some(Ordering::Greater)
-        } else if self.0 == other.0 {
-            Some(Ordering::Equal)
-        } else {
-            Some(Ordering::Less)
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for OrderedFloat {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        if self.0 > other.0 {
+            Ordering::Greater
+        } else if self.0 == other.0 {
+            Ordering::Equal
+        } else {
+            Ordering::Less
+        }
     }
 }
 
@@ -32,14 +48,16 @@ impl Eq for OrderedFloat {
     // pass
 }
 
-struct CandidateStatistics {
+/// Running mean and variance of a sequence of values, using Welford's
+/// algorithm.
+pub(super) struct CandidateStatistics {
     mean: f32,
     count: f32,
     m2: f32,
 }
 
 impl CandidateStatistics {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
             mean: 0.0,
             count: 0.0,
@@ -47,7 +65,7 @@ impl CandidateStatistics {
         }
     }
 
-    fn update(&mut self, new_value: f32) {
+    pub(super) fn update(&mut self, new_value: f32) {
         let delta = new_value - self.mean;
 
         self.count += 1.0;
@@ -55,15 +73,15 @@ impl CandidateStatistics {
         self.m2 += delta * (new_value - self.mean);
     }
 
-    fn mean(&self) -> f32 {
+    pub(super) fn mean(&self) -> f32 {
         self.mean
     }
 
-    fn count(&self) -> f32 {
+    pub(super) fn count(&self) -> f32 {
         self.count
     }
 
-    fn variance(&self) -> f32 {
+    pub(super) fn variance(&self) -> f32 {
         self.m2 / (self.count - 1.0)
     }
 }
@@ -71,7 +89,11 @@ impl CandidateStatistics {
 struct Candidate {
     at: Point,
     stats: CandidateStatistics,
-    child: Option<Box<SearchTree>>,
+    amaf: CandidateStatistics,
+    prior: f32,
+    policy: Option<Vec<f32>>,
+    virtual_loss: u32,
+    child: Option<Arc<SearchTree>>,
 }
 
 impl Candidate {
@@ -81,6 +103,10 @@ impl Candidate {
         Self {
             at,
             stats: CandidateStatistics::new(),
+            amaf: CandidateStatistics::new(),
+            prior: 0.0,
+            policy: None,
+            virtual_loss: 0,
             child: None
         }
     }
@@ -113,98 +139,197 @@ impl Candidate {
         self.stats.variance()
     }
 
-    fn ucb1(&self, total_sims: u32) -> OrderedFloat {
-        const C: f32 = 4.0;
+    /// Returns the estimated value of this candidate, where every probe that
+    /// is still in progress through this candidate counts as a loss (virtual
+    /// loss), so that concurrent probes are spread over different candidates.
+    ///
+    /// If `rave` is given, then the mean is mixed with the all-moves-as-first
+    /// (AMAF) mean, where the weight of the AMAF mean is `sqrt(k / (3n + k))`
+    /// for `n` probes and the equivalence parameter `k`. Candidates that have
+    /// not been probed yet are valued at their AMAF mean, or `0.5`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rave` - the equivalence parameter, if RAVE is enabled
+    ///
+    fn value(&self, rave: Option<f32>) -> f32 {
+        let sims = self.sims() + self.virtual_loss as f32;
+        let amaf = rave.filter(|_| self.amaf.count() > 0.0).map(|k| (k, self.amaf.mean()));
+
+        if sims == 0.0 {
+            amaf.map_or(0.5, |(_, amaf_mean)| amaf_mean)
+        } else {
+            let win_pct = self.wins() / sims;
+
+            match amaf {
+                Some((k, amaf_mean)) => {
+                    let beta = (k / (3.0 * sims + k)).sqrt();
+
+                    (1.0 - beta) * win_pct + beta * amaf_mean
+                },
+                None => win_pct,
+            }
+        }
+    }
+
+    /// Returns the UCB1 value of this candidate.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_sims` -
+    /// * `c` -
+    /// * `rave` - the equivalence parameter, if RAVE is enabled
+    ///
+    fn ucb1(&self, total_sims: u32, c: f32, rave: Option<f32>) -> OrderedFloat {
         let ln_total_sims = if total_sims == 0 { 0.0 } else { (total_sims as f32).ln() };
+        let sims = self.sims() + self.virtual_loss as f32;
 
-        OrderedFloat(if self.sims() == 0.0 {
-            0.5 + ln_total_sims.sqrt()
+        OrderedFloat(if sims == 0.0 {
+            self.value(rave) + ln_total_sims.sqrt()
         } else {
-            let win_pct = self.wins() / self.sims();
-            let ln_total_sims_pct = ln_total_sims / self.sims();
-
-            win_pct + C * ln_total_sims_pct.sqrt()
+            self.value(rave) + c * (ln_total_sims / sims).sqrt()
         })
     }
+
+    /// Returns the PUCT value of this candidate, as used by AlphaZero, where
+    /// the exploration term is weighted by the prior of the candidate.
+    ///
+    /// # Arguments
+    ///
+    /// * `total_sims` -
+    /// * `c_puct` -
+    /// * `rave` - the equivalence parameter, if RAVE is enabled
+    ///
+    fn puct(&self, total_sims: u32, c_puct: f32, rave: Option<f32>) -> OrderedFloat {
+        let sims = self.sims() + self.virtual_loss as f32;
+
+        OrderedFloat(self.value(rave) + c_puct * self.prior * (total_sims as f32).sqrt() / (1.0 + sims))
+    }
 }
 
+/// The outcome of a single probe, from the perspective of black.
 pub struct ProbeResult {
-    black: u16,
-    white: u16,
-    undecided: u16,
-    komi: f32
+    black_value: f32,
+    margin: f32,
+    ownership: Option<Vec<f32>>,
+    moves: Vec<(Point, Color)>,
 }
 
 impl ProbeResult {
-    fn score(goban: Goban, komi: f32) -> Self {
-        let mut black = 0;
-        let mut white = 0;
-        let mut undecided = 0;
-
-        for at in goban.iter() {
-            match goban.at(at) {
-                None => {
-                    let mut black_neighbours = 0;
-                    let mut white_neighbours = 0;
-
-                    for neighbour in at.neighbours().filter(|&n| goban[n].is_valid()) {
-                        match goban.at(neighbour) {
-                            Some(Color::Black) => { black_neighbours += 1 },
-                            Some(Color::White) => { white_neighbours += 1 },
-                            None => {},
-                        }
-                    }
-
-                    if black_neighbours + white_neighbours == 0 {
-                        undecided += 1;
-                    } else if black_neighbours == black_neighbours + white_neighbours {
-                        black += 1;
-                    } else if white_neighbours == black_neighbours + white_neighbours {
-                        white += 1;
-                    } else {
-                        undecided += 1;
-                    }
-                },
-                Some(Color::Black) => { black += 1; },
-                Some(Color::White) => { white += 1; },
-            }
+    /// Returns the outcome of a probe that ended in the given evaluation
+    /// `evaluation` of a position where `to_move` is the player to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluation` -
+    /// * `to_move` -
+    ///
+    fn from_evaluation(evaluation: Evaluation, to_move: Color) -> Self {
+        Self {
+            black_value: if to_move == Color::Black { evaluation.value } else { 1.0 - evaluation.value },
+            margin: evaluation.margin,
+            ownership: evaluation.ownership,
+            moves: evaluation.moves,
         }
+    }
 
-        Self { black, white, undecided, komi }
+    /// Returns the ownership of every point in the same order as
+    /// `Goban::iter`, where `1` means the point belongs to black, or `None`
+    /// if the evaluator did not provide any.
+    pub fn ownership(&self) -> Option<&[f32]> {
+        self.ownership.as_deref()
     }
 
-    pub fn winner(&self) -> (Color, f32) {
-        if self.undecided == 0 {
-            if (self.black as f32) > self.white as f32 + self.komi {
-                (Color::Black, 1.0)
-            } else {
-                (Color::White, 1.0)
-            }
-        } else {
-            let mean = self.white as f32 + self.komi as f32 - self.black as f32;
-            let std = 2.0 * self.undecided as f32 / 12.0;
-            let black_prob = Normal::new(mean, std);
+    /// Returns the probability that the given color `color` wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    ///
+    pub fn value(&self, color: Color) -> f32 {
+        match color {
+            Color::Black => self.black_value,
+            Color::White => 1.0 - self.black_value,
+        }
+    }
+
+    /// Returns the number of points black is ahead by, after komi.
+    pub fn margin(&self) -> f32 {
+        self.margin
+    }
+}
+
+/// The vital regions of both colors, as found by Benson's algorithm at the
+/// root of the search tree. Since the opponent can not undo unconditional
+/// life, these regions stay vital in every node below the root, so they are
+/// only found once per root and shared with every node expanded from it.
+struct VitalRegions {
+    black: Vec<Vec<Point>>,
+    white: Vec<Vec<Point>>,
+}
+
+impl VitalRegions {
+    fn new(goban: &Goban) -> Self {
+        Self {
+            black: Benson::new(goban, Color::Black).regions().to_vec(),
+            white: Benson::new(goban, Color::White).regions().to_vec(),
+        }
+    }
 
-            (Color::Black, black_prob.cdf(0.0) as f32)
+    fn of(&self, color: Color) -> &[Vec<Point>] {
+        match color {
+            Color::Black => &self.black,
+            Color::White => &self.white,
         }
     }
+
+    /// Returns the moves that can never change the outcome for the given color
+    /// `to_move`, i.e. moves inside the vital regions of the opponent, and
+    /// moves inside its own vital regions that contain no opponent stones to
+    /// capture in the given position `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    ///
+    fn hopeless_moves(&self, goban: &Goban, to_move: Color) -> Vec<Point> {
+        let own_regions = self.of(to_move).iter()
+            .filter(|region| region.iter().all(|&at| goban.at(at).is_none()));
+
+        own_regions.chain(self.of(to_move.opposite()).iter())
+            .flatten()
+            .copied()
+            .collect()
+    }
 }
 
+/// A node of the search tree, which can be probed by several threads at the
+/// same time. The candidates of each node are guarded by their own lock, which
+/// is never held while probing a child.
 pub struct SearchTree {
-    candidates: Vec<Candidate>,
+    candidates: Mutex<Vec<Candidate>>,
     pass_count: u8,
-    total_sims: u32,
+    total_sims: AtomicU32,
     to_move: Color,
+    exploration: f32,
+    evaluator: Arc<dyn Evaluator>,
+    rave: Option<f32>,
+    puct: Option<f32>,
+    prior: Option<Arc<dyn PriorProvider>>,
+    transpositions: Option<Arc<TranspositionTable>>,
+    vital_regions: Arc<VitalRegions>,
 }
 
 impl Debug for SearchTree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "sims: {}, pass: {}, to_move: {}", self.total_sims, self.pass_count, match self.to_move {
+        writeln!(f, "sims: {}, pass: {}, to_move: {}", self.total_sims(), self.pass_count, match self.to_move {
             Color::Black => "black",
             Color::White => "white",
         })?;
 
-        let mut candidates = self.candidates.iter().collect::<Vec<_>>();
+        let candidates = self.candidates();
+        let mut candidates = candidates.iter().collect::<Vec<_>>();
         candidates.sort_unstable_by_key(|cand| OrderedFloat(-cand.sims()));
 
         for cand in candidates.iter().take(10) {
@@ -223,13 +348,14 @@ impl Debug for SearchTree {
 
             writeln!(
                 f,
-                "{:7} / {:5} ({:.2} +/- {:.2}) {} (ucb1 {})",
+                "{:7} / {:5} ({:.2} +/- {:.2}) {} ({} {})",
                 cand.sims(),
                 cand.wins(),
                 if win_pct.is_finite() { win_pct } else { 0.0 },
                 cand.variance().sqrt(),
                 description,
-                cand.ucb1(self.total_sims),
+                if self.puct.is_some() { "puct" } else { "ucb1" },
+                self.selection_value(cand, self.total_sims()),
             )?;
         }
 
@@ -238,26 +364,158 @@ impl Debug for SearchTree {
 }
 
 impl SearchTree {
+    /// The default exploration constant of UCB1.
+    pub const EXPLORATION: f32 = 4.0;
+
     pub fn new(goban: &Goban, to_move: Color, pass_count: u8) -> Self {
+        Self::with_vital_regions(goban, to_move, pass_count, Arc::new(VitalRegions::new(goban)))
+    }
+
+    /// Returns a node for the given position `goban`, that skips the hopeless
+    /// moves according to the vital regions `vital_regions` of the root.
+    fn with_vital_regions(goban: &Goban, to_move: Color, pass_count: u8, vital_regions: Arc<VitalRegions>) -> Self {
+        let hopeless = vital_regions.hopeless_moves(goban, to_move);
+        let mut candidates = goban.iter()
+            .filter(|&at| goban.is_legal(at, to_move))
+            .filter(|at| !hopeless.contains(at))
+            .map(Candidate::new)
+            .chain([Candidate::pass()])
+            .collect::<Vec<_>>();
+        let uniform = 1.0 / candidates.len() as f32;
+        for candidate in candidates.iter_mut() {
+            candidate.prior = uniform;
+        }
+
         Self {
-            candidates: goban.iter()
-                .filter(|&at| goban.is_legal(at, to_move))
-                .map(|at| Candidate::new(at))
-                .chain([Candidate::pass()].into_iter())
-                .collect(),
+            candidates: Mutex::new(candidates),
             pass_count,
-            total_sims: 0,
+            total_sims: AtomicU32::new(0),
             to_move,
+            exploration: Self::EXPLORATION,
+            evaluator: Arc::new(LeafEvaluator::default()),
+            rave: None,
+            puct: None,
+            prior: None,
+            transpositions: None,
+            vital_regions,
+        }
+    }
+
+    /// Set the exploration constant `c` of UCB1, for this node and every node
+    /// that is expanded from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` -
+    ///
+    pub fn with_exploration(mut self, c: f32) -> Self {
+        self.exploration = c;
+        self
+    }
+
+    /// Set how the leaves of this node, and of every node that is expanded
+    /// from it, are evaluated.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` -
+    ///
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn Evaluator>) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Enable Rapid Action Value Estimation (RAVE) for this node and every
+    /// node that is expanded from it, using the given equivalence parameter
+    /// `equivalence`, which is the number of probes at which the plain mean
+    /// and the all-moves-as-first mean are weighted about equally.
+    ///
+    /// # Arguments
+    ///
+    /// * `equivalence` -
+    ///
+    pub fn with_rave(mut self, equivalence: f32) -> Self {
+        self.rave = Some(equivalence);
+        self
+    }
+
+    /// Select candidates using PUCT with the exploration constant `c_puct`
+    /// instead of UCB1, for this node and every node that is expanded from
+    /// it. Unless a prior is set, every candidate has the same prior.
+    ///
+    /// # Arguments
+    ///
+    /// * `c_puct` -
+    ///
+    pub fn with_puct(mut self, c_puct: f32) -> Self {
+        self.puct = Some(c_puct);
+        self
+    }
+
+    /// Set the prior probabilities of the candidates of this node, for the
+    /// given position `goban`, and use `prior` for every node that is
+    /// expanded from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `prior` -
+    /// * `goban` -
+    /// * `last_move` - the move that led to this position, or `None` if it
+    ///   was a pass or is not known
+    ///
+    pub fn with_prior(mut self, prior: Arc<dyn PriorProvider>, goban: &Goban, last_move: Option<Point>) -> Self {
+        let moves = self.candidates().iter()
+            .map(|cand| (!cand.is_pass()).then_some(cand.at))
+            .collect::<Vec<_>>();
+
+        self.set_priors(&prior.priors(goban, self.to_move, last_move, &moves));
+        self.prior = Some(prior);
+        self
+    }
+
+    /// Set the prior of every candidate to its weight in `weights`, divided
+    /// by the sum of all weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `weights` -
+    ///
+    fn set_priors(&mut self, weights: &[f32]) {
+        let candidates = self.candidates.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let total = weights.iter().sum::<f32>();
+
+        if total > 0.0 {
+            for (candidate, weight) in candidates.iter_mut().zip(weights) {
+                candidate.prior = weight / total;
+            }
         }
     }
 
+    /// Set the transposition table that is shared by this node and every
+    /// node that is expanded from it, so that positions that are reached
+    /// through different move orders share their statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `transpositions` -
+    ///
+    pub(super) fn with_transpositions(mut self, transpositions: Arc<TranspositionTable>) -> Self {
+        self.transpositions = Some(transpositions);
+        self
+    }
+
+    fn candidates(&self) -> MutexGuard<'_, Vec<Candidate>> {
+        self.candidates.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     fn as_sgf_tree(&self) -> String {
         const LETTERS: [char; 26] = [
             'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm',
             'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z'
         ];
 
-        let mut candidates = self.candidates.iter()
+        let candidates = self.candidates();
+        let mut candidates = candidates.iter()
             .filter(|cand| cand.sims() > 0.0)
             .collect::<Vec<_>>();
         candidates.sort_unstable_by_key(|cand| -(cand.sims() as i64));
@@ -307,7 +565,7 @@ impl SearchTree {
             })
             .collect::<String>();
 
-        if black_list.len() > 0 {
+        if !black_list.is_empty() {
             format!("{}{}", property_name, black_list)
         } else {
             "".into()
@@ -325,20 +583,45 @@ impl SearchTree {
     }
 
     pub fn total_sims(&self) -> u32 {
-        self.total_sims
+        self.total_sims.load(atomic::Ordering::Relaxed)
     }
 
-    pub fn is_done(&self, prob: f32) -> bool {
+    /// Returns whether every candidate has been visited at least `min_visits`
+    /// times, and the standard deviation of each candidate is below
+    /// `1 - prob`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` -
+    /// * `min_visits` -
+    ///
+    pub fn is_done(&self, prob: f32, min_visits: u32) -> bool {
         let inv_prob2 = (1.0 - prob) * (1.0 - prob);
 
-        self.candidates.iter()
-            .all(|cand| cand.sims() > 1.0 && cand.variance() < inv_prob2)
+        self.candidates().iter()
+            .all(|cand| cand.sims() >= min_visits.max(2) as f32 && cand.variance() < inv_prob2)
+    }
+
+    /// Returns the most visited candidate, where ties are broken in favour of
+    /// the candidate with the highest mean.
+    fn most_visited(candidates: &[Candidate]) -> &Candidate {
+        candidates.iter()
+            .max_by_key(|cand| (OrderedFloat(cand.sims()), OrderedFloat(cand.mean())))
+            .unwrap()
+    }
+
+    /// Returns the estimated probability that the player to move wins, which
+    /// is the mean of the most visited candidate.
+    pub fn win_rate(&self) -> f32 {
+        let candidates = self.candidates();
+        let most_sims = Self::most_visited(&candidates);
+
+        most_sims.mean()
     }
 
     pub fn winner(&self) -> Color {
-        let most_sims = self.candidates.iter()
-            .max_by_key(|cand| OrderedFloat(cand.sims()))
-            .unwrap();
+        let candidates = self.candidates();
+        let most_sims = Self::most_visited(&candidates);
 
         if most_sims.mean() > 0.5 {
             self.to_move
@@ -347,56 +630,345 @@ impl SearchTree {
         }
     }
 
-    fn to_move(&self) -> Color {
-        self.to_move
+    /// Returns the most visited move, or `None` if the most visited move is to
+    /// pass.
+    pub fn best_move(&self) -> Option<Point> {
+        let candidates = self.candidates();
+        let most_sims = Self::most_visited(&candidates);
+
+        if most_sims.is_pass() {
+            None
+        } else {
+            Some(most_sims.at)
+        }
     }
 
-    fn next_candidate<'a>(&'a mut self) -> &'a mut Candidate {
-        let total_sims = self.total_sims;
+    /// Returns the subtree of the given move `at`, where `None` is a pass, or
+    /// `None` if the move is not a candidate or has not been expanded yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub(super) fn child(&self, at: Option<Point>) -> Option<Arc<SearchTree>> {
+        self.candidates().iter()
+            .find(|cand| if cand.is_pass() { at.is_none() } else { at == Some(cand.at) })
+            .and_then(|cand| cand.child.clone())
+    }
 
-        self.candidates.iter_mut()
-            .max_by_key(|cand| cand.ucb1(total_sims))
-            .unwrap()
+    /// Returns the sequence of most visited moves starting at this node,
+    /// where `None` is a pass.
+    fn principal_variation(&self) -> Vec<Option<Point>> {
+        let candidates = self.candidates();
+
+        match Some(Self::most_visited(&candidates)).filter(|cand| cand.sims() > 0.0) {
+            None => vec! [],
+            Some(cand) => {
+                let mut pv = vec! [if cand.is_pass() { None } else { Some(cand.at) }];
+                if let Some(child) = &cand.child {
+                    pv.extend(child.principal_variation());
+                }
+
+                pv
+            }
+        }
+    }
+
+    /// Returns the statistics of every candidate move at this node, ordered
+    /// by the number of visits, where ties are broken in favour of the
+    /// candidate with the highest mean.
+    pub fn analysis(&self) -> Analysis {
+        let total_sims = self.total_sims();
+        let candidates = self.candidates();
+
+        // reversed, so that the stable sort breaks any remaining ties the
+        // same way as `most_visited`
+        let mut moves = candidates.iter()
+            .rev()
+            .map(|cand| {
+                let at = if cand.is_pass() { None } else { Some(cand.at) };
+                let mut pv = vec! [at];
+                if let Some(child) = &cand.child {
+                    pv.extend(child.principal_variation());
+                }
+
+                MoveAnalysis {
+                    at,
+                    visits: cand.sims() as u32,
+                    mean: if cand.sims() > 0.0 { cand.mean() } else { 0.0 },
+                    variance: if cand.sims() > 1.0 { cand.variance() } else { 0.0 },
+                    ucb: self.selection_value(cand, total_sims).0,
+                    prior: cand.prior,
+                    pv,
+                    child: cand.child.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        moves.sort_by_key(|mv| (std::cmp::Reverse(mv.visits), OrderedFloat(-mv.mean)));
+
+        Analysis { to_move: self.to_move, total_sims, moves }
     }
 
-    fn probe_candidate(
-        candidate: &mut Candidate,
-        goban: Goban,
-        to_move: Color,
-        pass_count: u8,
-        komi: f32,
-    ) -> ProbeResult
-    {
-        let probe_result = if pass_count >= 2 {
-            ProbeResult::score(goban, komi)
-        } else if let Some(next_child) = candidate.child.as_mut() {
-            next_child.probe(goban, komi)
-        } else if candidate.sims() >= 1.0 {
-            candidate.child = Some(Box::new(SearchTree::new(&goban, to_move.opposite(), pass_count)));
-            candidate.child.as_mut().unwrap().probe(goban, komi)
+    /// Returns the value that is maximized when selecting the next candidate
+    /// to probe, which is either its PUCT or its UCB1 value.
+    fn selection_value(&self, candidate: &Candidate, total_sims: u32) -> OrderedFloat {
+        match self.puct {
+            Some(c_puct) => candidate.puct(total_sims, c_puct, self.rave),
+            None => candidate.ucb1(total_sims, self.exploration, self.rave),
+        }
+    }
+
+    /// Returns the index of the candidate with the highest selection value,
+    /// that is legal in the given position `goban`. The candidates of a node
+    /// that is shared through the transposition table were generated for
+    /// the position of whichever probe reached it first, so they might not
+    /// all be legal in `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` -
+    /// * `goban` -
+    ///
+    fn next_candidate(&self, candidates: &[Candidate], goban: &Goban) -> usize {
+        let total_sims = self.total_sims();
+        let is_legal = |i: &usize| candidates[*i].is_pass() || goban.is_legal(candidates[*i].at, self.to_move);
+        let best = (0..candidates.len())
+            .max_by_key(|&i| self.selection_value(&candidates[i], total_sims))
+            .unwrap();
+
+        if is_legal(&best) {
+            best
         } else {
-            ProbeResult::score(goban, komi)
-        };
+            (0..candidates.len())
+                .filter(is_legal)
+                .max_by_key(|&i| self.selection_value(&candidates[i], total_sims))
+                .unwrap()
+        }
+    }
 
-        let (winner, prob) = probe_result.winner();
-        candidate.update(if winner == to_move { prob } else { 1.0 - prob });
+    /// Returns a new child of this node for the given position `goban`, where
+    /// it is the opponent's turn to move. The priors of the child are taken
+    /// from `policy` if the evaluation of the position provided one.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `last_move` -
+    /// * `pass_count` -
+    /// * `policy` -
+    ///
+    fn expand(&self, goban: &Goban, last_move: Option<Point>, pass_count: u8, policy: Option<Vec<f32>>) -> Self {
+        let mut child = SearchTree::with_vital_regions(goban, self.to_move.opposite(), pass_count, self.vital_regions.clone())
+            .with_exploration(self.exploration)
+            .with_evaluator(self.evaluator.clone());
+        child.rave = self.rave;
+        child.puct = self.puct;
+
+        match (policy, &self.prior) {
+            (Some(policy), _) => {
+                let width = goban.width();
+                let weights = child.candidates().iter()
+                    .map(|cand| {
+                        let i = if cand.is_pass() { width * goban.height() } else { (cand.at.y() - 1) * width + (cand.at.x() - 1) };
+
+                        policy.get(i).copied().unwrap_or(0.0)
+                    })
+                    .collect::<Vec<_>>();
+
+                child.set_priors(&weights);
+                child.prior = self.prior.clone();
+            },
+            (None, Some(prior)) => {
+                child = child.with_prior(prior.clone(), goban, last_move);
+            },
+            (None, None) => {},
+        }
 
-        probe_result
+        match &self.transpositions {
+            Some(transpositions) => child.with_transpositions(transpositions.clone()),
+            None => child,
+        }
+    }
+
+    /// Returns the evaluation of the given leaf position `goban`, where it is
+    /// the opponent's turn to move, together with the policy of the leaf if
+    /// the evaluator provided one.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `last_move` -
+    /// * `pass_count` -
+    /// * `komi` -
+    /// * `rng` -
+    ///
+    fn evaluate(&self, goban: Goban, last_move: Option<Point>, pass_count: u8, komi: f32, rng: &mut Random) -> (ProbeResult, Option<Vec<f32>>) {
+        let to_move = self.to_move.opposite();
+        let leaf = Leaf::new(goban, to_move, last_move, pass_count, komi);
+        let mut evaluation = self.evaluator.evaluate(&leaf, rng);
+        let policy = evaluation.policy.take();
+
+        (ProbeResult::from_evaluation(evaluation, to_move), policy)
+    }
+
+    /// Update the all-moves-as-first (AMAF) statistics of every candidate
+    /// that was played first by the player to move, at this node or anywhere
+    /// later during the probe.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` -
+    /// * `moves` - the stones played since this node, latest first
+    /// * `value` - the value of the probe for the player to move
+    ///
+    fn update_amaf(&self, candidates: &mut [Candidate], moves: &[(Point, Color)], value: f32) {
+        let first_color = moves.iter().copied().collect::<HashMap<_, _>>();
+
+        for candidate in candidates.iter_mut().filter(|cand| !cand.is_pass()) {
+            if first_color.get(&candidate.at) == Some(&self.to_move) {
+                candidate.amaf.update(value);
+            }
+        }
     }
 
-    pub fn probe(&mut self, mut goban: Goban, komi: f32) -> ProbeResult {
-        let to_move = self.to_move();
-        let pass_count = self.pass_count;
-        let next_candidate = self.next_candidate();
-        let result = if !next_candidate.is_pass() {
-            goban.play(next_candidate.at, to_move);
-            Self::probe_candidate(next_candidate, goban, to_move, 0, komi)
+    /// Perform a single probe from this node, which may be called by several
+    /// threads at the same time. The candidate that is probed receives a
+    /// virtual loss until the probe is finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `komi` -
+    /// * `rng` - the random number generator used by playouts
+    ///
+    pub fn probe(&self, mut goban: Goban, komi: f32, rng: &mut Random) -> ProbeResult {
+        let (index, at, child, is_expandable, policy) = {
+            let mut candidates = self.candidates();
+            let index = self.next_candidate(&candidates, &goban);
+            let candidate = &mut candidates[index];
+            candidate.virtual_loss += 1;
+
+            let is_expandable = candidate.sims() >= 1.0;
+            let policy = if is_expandable && candidate.child.is_none() { candidate.policy.take() } else { None };
+
+            (index, (!candidate.is_pass()).then_some(candidate.at), candidate.child.clone(), is_expandable, policy)
+        };
+
+        let pass_count = match at {
+            Some(at) => {
+                goban.play(at, self.to_move);
+                0
+            },
+            None => self.pass_count + 1,
+        };
+
+        let mut probe_result = if pass_count >= 2 {
+            self.evaluate(goban, at, pass_count, komi, rng).0
+        } else if let Some(child) = child {
+            child.probe(goban, komi, rng)
+        } else if is_expandable {
+            let child = match &self.transpositions {
+                Some(transpositions) => {
+                    let key = Key::new(&goban, self.to_move.opposite(), pass_count);
+
+                    transpositions.get_or_insert_with(key, || self.expand(&goban, at, pass_count, policy))
+                },
+                None => Arc::new(self.expand(&goban, at, pass_count, policy)),
+            };
+            let child = self.candidates()[index].child.get_or_insert(child).clone();
+
+            child.probe(goban, komi, rng)
         } else {
-            Self::probe_candidate(next_candidate, goban, to_move, pass_count + 1, komi)
+            let (probe_result, policy) = self.evaluate(goban, at, pass_count, komi, rng);
+            if policy.is_some() {
+                self.candidates()[index].policy = policy;
+            }
+
+            probe_result
         };
 
-        self.total_sims += 1;
+        if let (Some(at), Some(_)) = (at, self.rave) {
+            probe_result.moves.push((at, self.to_move));
+        }
+
+        let value = probe_result.value(self.to_move);
+        {
+            let mut candidates = self.candidates();
+            let candidate = &mut candidates[index];
+            candidate.virtual_loss -= 1;
+            candidate.update(value);
+
+            if self.rave.is_some() {
+                self.update_amaf(&mut candidates, &probe_result.moves, value);
+            }
+        }
+
+        self.total_sims.fetch_add(1, atomic::Ordering::Relaxed);
+
+        probe_result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the number of AMAF updates of the candidate at `at`.
+    fn amaf_count(search_tree: &SearchTree, at: Point) -> f32 {
+        search_tree.candidates().iter().find(|cand| cand.at == at).unwrap().amaf.count()
+    }
+
+    #[test]
+    fn amaf_skips_points_played_first_by_opponent() {
+        let search_tree = SearchTree::new(&Goban::new(3, 3), Color::Black, 0).with_rave(100.0);
+        let ours = Point::from((0u8, 0u8));
+        let theirs = Point::from((1u8, 1u8));
+
+        search_tree.update_amaf(&mut search_tree.candidates(), &[(theirs, Color::White), (ours, Color::Black)], 1.0);
+
+        assert_eq!(amaf_count(&search_tree, ours), 1.0);
+        assert_eq!(amaf_count(&search_tree, theirs), 0.0);
+    }
+
+    #[test]
+    fn amaf_uses_earliest_play_of_point() {
+        let search_tree = SearchTree::new(&Goban::new(3, 3), Color::Black, 0).with_rave(100.0);
+        let recaptured = Point::from((0u8, 0u8));
+        let replayed = Point::from((2u8, 2u8));
+
+        // latest first, so `recaptured` was played by black after white's
+        // stone on it was captured, and `replayed` the other way around
+        search_tree.update_amaf(
+            &mut search_tree.candidates(),
+            &[
+                (recaptured, Color::Black),
+                (replayed, Color::White),
+                (recaptured, Color::White),
+                (replayed, Color::Black),
+            ],
+            1.0
+        );
+
+        assert_eq!(amaf_count(&search_tree, recaptured), 0.0);
+        assert_eq!(amaf_count(&search_tree, replayed), 1.0);
+    }
+
+    #[test]
+    fn value_blends_with_amaf() {
+        let mut candidate = Candidate::new((0u8, 0u8).into());
+        assert_eq!(candidate.value(Some(9.0)), 0.5);
+
+        candidate.amaf.update(0.0);
+        assert_eq!(candidate.value(Some(9.0)), 0.0);
+        assert_eq!(candidate.value(None), 0.5);
+
+        for _ in 0..3 {
+            candidate.update(1.0);
+        }
+
+        let beta = (9.0f32 / (3.0 * 3.0 + 9.0)).sqrt();
 
-        result
+        assert_eq!(candidate.value(None), 1.0);
+        assert!((candidate.value(Some(9.0)) - (1.0 - beta)).abs() < 1e-6);
     }
 }
@@ -0,0 +1,160 @@
+use crate::{Color, Goban, Point};
+use super::chain::Chain;
+
+/// Returns whether playing a stone of the given color `color` at `at` is a
+/// useful approach move, i.e. it is legal and either captures something or
+/// does not leave the played stone in atari.
+///
+/// # Arguments
+///
+/// * `goban` -
+/// * `at` -
+/// * `color` -
+///
+fn is_approach_move(goban: &Goban, at: Point, color: Color) -> bool {
+    if !goban.is_legal(at, color) {
+        return false;
+    }
+
+    let is_capture = at.neighbours()
+        .filter(|&n| goban[n].is_valid() && goban.at(n) == Some(color.opposite()))
+        .any(|n| Chain::at(goban, n).is_some_and(|chain| chain.liberties().len() == 1));
+
+    if is_capture {
+        return true;
+    }
+
+    let mut after = goban.clone();
+    after.play(at, color);
+
+    Chain::at(&after, at).is_some_and(|chain| chain.liberties().len() >= 2)
+}
+
+/// The chains that are alive in seki, i.e. chains of both colors that share
+/// liberties which neither player can fill without putting their own chain
+/// in atari.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Seki {
+    stones: Vec<Point>,
+    liberties: Vec<Point>,
+}
+
+impl Seki {
+    /// Returns the chains in seki in the given position `goban`.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    ///
+    pub fn new(goban: &Goban) -> Self {
+        let mut seki = Self::default();
+
+        for at in goban.iter().filter(|&at| goban.at(at).is_none()) {
+            let neighbours = at.neighbours()
+                .filter(|&n| goban[n].is_valid())
+                .filter_map(|n| Chain::at(goban, n))
+                .filter(|chain| chain.liberties().len() >= 2)
+                .collect::<Vec<_>>();
+            let touches = |color: Color| neighbours.iter().any(|chain| chain.color() == color);
+
+            if !touches(Color::Black) || !touches(Color::White) {
+                continue;
+            }
+
+            if is_approach_move(goban, at, Color::Black) || is_approach_move(goban, at, Color::White) {
+                continue;
+            }
+
+            seki.liberties.push(at);
+            for chain in &neighbours {
+                for &stone in chain.stones() {
+                    if !seki.stones.contains(&stone) {
+                        seki.stones.push(stone);
+                    }
+                }
+            }
+        }
+
+        seki
+    }
+
+    /// Returns all stones that are alive in seki.
+    pub fn stones(&self) -> &[Point] {
+        &self.stones
+    }
+
+    /// Returns the shared liberties of the chains in seki, which are dame
+    /// under both area and territory scoring.
+    pub fn liberties(&self) -> &[Point] {
+        &self.liberties
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stones.is_empty()
+    }
+
+    /// Returns whether the given point `at` is either a stone in seki, or one
+    /// of their shared liberties.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    ///
+    pub fn contains(&self, at: Point) -> bool {
+        self.stones.contains(&at) || self.liberties.contains(&at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ```
+    /// . x x . o o .
+    /// ```
+    #[test]
+    fn one_eye_each_and_shared_liberty_is_seki() {
+        let mut goban = Goban::new(7, 1);
+        goban.play((1u8, 0u8).into(), Color::Black);
+        goban.play((2u8, 0u8).into(), Color::Black);
+        goban.play((4u8, 0u8).into(), Color::White);
+        goban.play((5u8, 0u8).into(), Color::White);
+
+        let seki = Seki::new(&goban);
+
+        assert_eq!(seki.liberties(), &[(3u8, 0u8).into()]);
+        assert_eq!(seki.stones().len(), 4);
+        assert!(seki.contains((1u8, 0u8).into()));
+        assert!(!seki.contains((0u8, 0u8).into()));
+    }
+
+    /// ```
+    /// . x x . . o o .
+    /// ```
+    #[test]
+    fn separate_liberties_are_not_seki() {
+        let mut goban = Goban::new(8, 1);
+        goban.play((1u8, 0u8).into(), Color::Black);
+        goban.play((2u8, 0u8).into(), Color::Black);
+        goban.play((5u8, 0u8).into(), Color::White);
+        goban.play((6u8, 0u8).into(), Color::White);
+
+        assert!(Seki::new(&goban).is_empty());
+    }
+
+    /// ```
+    /// x x .
+    /// o x x
+    /// . x .
+    /// ```
+    #[test]
+    fn atari_is_not_seki() {
+        let mut goban = Goban::new(3, 3);
+        for at in [(0u8, 0u8), (1, 0), (1, 1), (2, 1), (1, 2)] {
+            goban.play(at.into(), Color::Black);
+        }
+        goban.play((0u8, 1u8).into(), Color::White);
+
+        assert!(Seki::new(&goban).is_empty());
+    }
+}
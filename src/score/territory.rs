@@ -0,0 +1,307 @@
+use crate::{array2d::Array2D, Color, Goban, Point};
+use super::area::flood_fill;
+
+/// The points of a single player under territory scoring.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Breakdown {
+    territory: usize,
+    captures: usize,
+    dead_stones: usize,
+}
+
+impl Breakdown {
+    /// Returns the number of empty points (including the points of dead
+    /// stones) surrounded by this player.
+    pub fn territory(&self) -> usize {
+        self.territory
+    }
+
+    /// Returns the number of stones captured by this player during the game.
+    pub fn captures(&self) -> usize {
+        self.captures
+    }
+
+    /// Returns the number of dead stones of the opponent that are removed at
+    /// the end of the game, and count as prisoners for this player.
+    pub fn dead_stones(&self) -> usize {
+        self.dead_stones
+    }
+
+    /// Returns the total number of points, excluding komi.
+    pub fn total(&self) -> usize {
+        self.territory + self.captures + self.dead_stones
+    }
+}
+
+/// Territory scoring, as used by the Japanese and Korean rules, where a
+/// player's score is their territory plus the number of prisoners they hold.
+pub struct Territory<'a> {
+    goban: &'a Goban,
+    komi: f32,
+    dead: Vec<Point>,
+    seki: Vec<Point>,
+    black_captures: usize,
+    white_captures: usize,
+}
+
+impl<'a> Territory<'a> {
+    /// Returns territory scoring for the given position `goban`, where all
+    /// stones are alive and no stones have been captured.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `komi` -
+    ///
+    pub fn new(goban: &'a Goban, komi: f32) -> Self {
+        Self {
+            goban,
+            komi,
+            dead: vec! [],
+            seki: vec! [],
+            black_captures: 0,
+            white_captures: 0,
+        }
+    }
+
+    /// Mark the stones at the given points as dead.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` -
+    ///
+    pub fn dead_stones(mut self, points: impl IntoIterator<Item=Point>) -> Self {
+        self.dead.extend(points);
+        self
+    }
+
+    /// Mark the stones at the given points as alive in seki, which means that
+    /// no empty point next to them counts as territory.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` -
+    ///
+    pub fn seki(mut self, points: impl IntoIterator<Item=Point>) -> Self {
+        self.seki.extend(points);
+        self
+    }
+
+    /// Set the number of stones captured by the given color `color` during
+    /// the game.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` -
+    /// * `count` -
+    ///
+    pub fn captures(mut self, color: Color, count: usize) -> Self {
+        match color {
+            Color::Black => { self.black_captures = count },
+            Color::White => { self.white_captures = count },
+        }
+        self
+    }
+
+    /// Returns the territory score of the position.
+    pub fn score(&self) -> TerritoryScore {
+        let goban = self.goban;
+        let (width, height) = (goban.width() + 2, goban.height() + 2);
+        let mut is_dead = Array2D::new(width, height, false);
+        let mut is_seki = Array2D::new(width, height, false);
+        let mut owners = Array2D::new(width, height, None);
+        let mut visited = Array2D::new(width, height, false);
+        let mut black = Breakdown { captures: self.black_captures, ..Breakdown::default() };
+        let mut white = Breakdown { captures: self.white_captures, ..Breakdown::default() };
+
+        for &at in &self.dead {
+            match goban.at(at) {
+                Some(Color::Black) if !is_dead[(at.x(), at.y())] => { white.dead_stones += 1 },
+                Some(Color::White) if !is_dead[(at.x(), at.y())] => { black.dead_stones += 1 },
+                _ => { continue }
+            }
+
+            is_dead[(at.x(), at.y())] = true;
+        }
+
+        for &at in &self.seki {
+            is_seki[(at.x(), at.y())] = true;
+        }
+
+        let stone_at = |at: Point| if is_dead[(at.x(), at.y())] { None } else { goban.at(at) };
+
+        for at in goban.iter() {
+            if stone_at(at).is_some() || visited[(at.x(), at.y())] {
+                continue;
+            }
+
+            let (region, owner) = flood_fill(goban, at, &mut visited, stone_at);
+            let is_in_seki = region.iter()
+                .flat_map(|other| other.neighbours())
+                .any(|other| goban[other].is_valid() && is_seki[(other.x(), other.y())]);
+
+            if is_in_seki {
+                continue;
+            }
+
+            for &other in &region {
+                owners[(other.x(), other.y())] = owner;
+            }
+
+            match owner {
+                Some(Color::Black) => { black.territory += region.len() },
+                Some(Color::White) => { white.territory += region.len() },
+                None => {},
+            }
+        }
+
+        TerritoryScore { owners, black, white, komi: self.komi }
+    }
+}
+
+/// The result of territory scoring.
+#[derive(Clone)]
+pub struct TerritoryScore {
+    owners: Array2D<Option<Color>>,
+    black: Breakdown,
+    white: Breakdown,
+    komi: f32,
+}
+
+impl TerritoryScore {
+    /// Returns the points of black.
+    pub fn black(&self) -> Breakdown {
+        self.black
+    }
+
+    /// Returns the points of white, excluding komi.
+    pub fn white(&self) -> Breakdown {
+        self.white
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.komi
+    }
+
+    /// Returns the color whose territory the given point `at` is, or `None`
+    /// if it is not territory of either player, e.g. a living stone or dame.
+    pub fn territory_at(&self, at: Point) -> Option<Color> {
+        self.owners[(at.x(), at.y())]
+    }
+
+    /// Returns the number of points black is ahead by, after komi.
+    pub fn margin(&self) -> f32 {
+        self.black.total() as f32 - self.white.total() as f32 - self.komi
+    }
+
+    /// Returns the winner, or `None` if the game is a draw.
+    pub fn winner(&self) -> Option<Color> {
+        let margin = self.margin();
+
+        if margin > 0.0 {
+            Some(Color::Black)
+        } else if margin < 0.0 {
+            Some(Color::White)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the result in the SGF `RE` notation, e.g. `W+6.5` or `0` for a
+    /// draw.
+    pub fn result(&self) -> String {
+        match self.winner() {
+            Some(Color::Black) => format!("B+{}", self.margin()),
+            Some(Color::White) => format!("W+{}", -self.margin()),
+            None => "0".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{record::GameRecord, score::Seki, sgf};
+
+    /// ```
+    /// . x o . .
+    /// x x o . .
+    /// . x o o o
+    /// x x o x .
+    /// . x o . .
+    /// ```
+    fn goban() -> Goban {
+        let mut goban = Goban::new(5, 5);
+        for at in [(1u8, 0u8), (0, 1), (1, 1), (1, 2), (0, 3), (1, 3), (1, 4), (3, 3)] {
+            goban.play(at.into(), Color::Black);
+        }
+        for at in [(2u8, 0u8), (2, 1), (2, 2), (3, 2), (4, 2), (2, 3), (2, 4)] {
+            goban.play(at.into(), Color::White);
+        }
+
+        goban
+    }
+
+    #[test]
+    fn dead_stones_count_as_prisoners_and_territory() {
+        let goban = goban();
+        let score = Territory::new(&goban, 6.5)
+            .dead_stones([(3u8, 3u8).into()])
+            .captures(Color::White, 2)
+            .score();
+
+        assert_eq!(score.black(), Breakdown { territory: 3, captures: 0, dead_stones: 0 });
+        assert_eq!(score.white(), Breakdown { territory: 8, captures: 2, dead_stones: 1 });
+        assert_eq!(score.territory_at((3u8, 3u8).into()), Some(Color::White));
+        assert_eq!(score.territory_at((1u8, 1u8).into()), None);
+        assert_eq!(score.result(), "W+14.5");
+    }
+
+    #[test]
+    fn seki_has_no_territory() {
+        let goban = goban();
+        let score = Territory::new(&goban, 0.0)
+            .seki([(1u8, 0u8).into(), (0u8, 1u8).into(), (1u8, 1u8).into(), (1u8, 2u8).into()])
+            .score();
+
+        assert_eq!(score.black().territory(), 1);
+        assert_eq!(score.territory_at((0u8, 0u8).into()), None);
+        assert_eq!(score.territory_at((0u8, 4u8).into()), Some(Color::Black));
+    }
+
+    /// ```
+    /// . x x . o o .
+    /// ```
+    #[test]
+    fn detected_seki_has_no_territory() {
+        let mut goban = Goban::new(7, 1);
+        goban.play((1u8, 0u8).into(), Color::Black);
+        goban.play((2u8, 0u8).into(), Color::Black);
+        goban.play((4u8, 0u8).into(), Color::White);
+        goban.play((5u8, 0u8).into(), Color::White);
+
+        let seki = Seki::new(&goban);
+        let score = Territory::new(&goban, 0.0).seki(seki.stones().iter().copied()).score();
+
+        assert_eq!((score.black().territory(), score.white().territory()), (0, 0));
+        assert_eq!(score.territory_at((0u8, 0u8).into()), None);
+    }
+
+    #[test]
+    fn japanese_game_matches_recorded_result() {
+        let text = "(;GM[1]FF[4]SZ[5]KM[0.5]RU[Japanese]RE[W+5.5];B[bc];W[cc];B[bb];W[cb];B[bd];W[cd];B[ba];W[ca]\
+                    ;B[be];W[ce];B[db];W[da];B[ab];W[dc];B[ad];W[eb];B[];W[])";
+        let record = GameRecord::from_sgf(&sgf::parse(text).unwrap()[0]).unwrap();
+        let mut replay = record.replay();
+        for result in replay.by_ref() {
+            result.unwrap();
+        }
+
+        let score = Territory::new(replay.goban(), record.komi())
+            .captures(Color::Black, replay.captures(Color::Black))
+            .captures(Color::White, replay.captures(Color::White))
+            .score();
+
+        assert_eq!(Some(score.result().as_str()), record.result());
+    }
+}
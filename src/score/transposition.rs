@@ -0,0 +1,174 @@
+use crate::{Color, Goban};
+use super::search_tree::SearchTree;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+/// The number of entries in each bucket of the table.
+const WAYS: usize = 4;
+
+/// Identifies a node of the search tree, regardless of the sequence of moves
+/// that led to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Key {
+    hash: u32,
+    to_move: Color,
+    pass_count: u8,
+}
+
+impl Key {
+    /// Returns the key of the given position `goban`, where it is the given
+    /// color `to_move` to play after `pass_count` consecutive passes.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    /// * `to_move` -
+    /// * `pass_count` -
+    ///
+    pub(super) fn new(goban: &Goban, to_move: Color, pass_count: u8) -> Self {
+        Self { hash: goban.hash(), to_move, pass_count }
+    }
+}
+
+struct Entry {
+    key: Key,
+    node: Weak<SearchTree>,
+}
+
+/// A set-associative table of search tree nodes, so that the same position
+/// reached through different move orders shares its statistics. The table
+/// holds at most a fixed number of entries, and when a bucket is full the
+/// entry whose node has received the fewest probes is replaced.
+///
+/// The table does not keep nodes alive, those are still owned by their
+/// parents in the search tree, so the bound on the number of entries limits
+/// the memory of the index only, not of the nodes it refers to.
+///
+/// Nodes are matched by the hash of their position only, so a node can be
+/// shared by positions that differ because of a hash collision, or that have
+/// a different ko history. The search re-validates every move it selects
+/// from a node against the actual position for that reason.
+pub(super) struct TranspositionTable {
+    buckets: Vec<Mutex<Vec<Entry>>>,
+}
+
+impl TranspositionTable {
+    /// Returns an empty table that holds at most `num_entries` nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_entries` -
+    ///
+    pub(super) fn new(num_entries: usize) -> Self {
+        Self {
+            buckets: (0..num_entries.div_ceil(WAYS).max(1))
+                .map(|_| Mutex::new(Vec::with_capacity(WAYS)))
+                .collect(),
+        }
+    }
+
+    /// Returns the node with the given key `key`, or inserts the node created
+    /// by `create` if the table does not contain it.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` -
+    /// * `create` -
+    ///
+    pub(super) fn get_or_insert_with(&self, key: Key, create: impl FnOnce() -> SearchTree) -> Arc<SearchTree> {
+        let bucket = &self.buckets[key.hash as usize % self.buckets.len()];
+        let mut entries = bucket.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(node) = entries.iter().filter(|entry| entry.key == key).find_map(|entry| entry.node.upgrade()) {
+            return node;
+        }
+
+        let node = Arc::new(create());
+        let entry = Entry { key, node: Arc::downgrade(&node) };
+
+        entries.retain(|entry| entry.key != key && entry.node.strong_count() > 0);
+        if entries.len() < WAYS {
+            entries.push(entry);
+        } else {
+            let least_visited = (0..entries.len())
+                .min_by_key(|&i| entries[i].node.upgrade().map_or(0, |node| node.total_sims()))
+                .unwrap();
+
+            entries[least_visited] = entry;
+        }
+
+        node
+    }
+
+    /// Returns the number of nodes in the table that are still alive.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buckets.iter()
+            .map(|bucket| {
+                bucket.lock().unwrap_or_else(PoisonError::into_inner).iter()
+                    .filter(|entry| entry.node.strong_count() > 0)
+                    .count()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::Random;
+
+    #[test]
+    fn same_key_shares_node() {
+        let table = TranspositionTable::new(16);
+        let goban = Goban::new(3, 3);
+        let key = Key::new(&goban, Color::Black, 0);
+        let a = table.get_or_insert_with(key, || SearchTree::new(&goban, Color::Black, 0));
+        let b = table.get_or_insert_with(key, || SearchTree::new(&goban, Color::Black, 0));
+        let c = table.get_or_insert_with(Key::new(&goban, Color::White, 0), || SearchTree::new(&goban, Color::White, 0));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn table_is_bounded() {
+        let table = TranspositionTable::new(8);
+        let mut nodes = vec! [];
+
+        for x in 0..5u8 {
+            for y in 0..5u8 {
+                let mut goban = Goban::new(5, 5);
+                goban.play((x, y).into(), Color::Black);
+
+                let key = Key::new(&goban, Color::White, 0);
+                nodes.push(table.get_or_insert_with(key, || SearchTree::new(&goban, Color::White, 0)));
+            }
+        }
+
+        assert!(table.len() <= 8);
+        drop(nodes);
+        assert_eq!(table.len(), 0);
+    }
+
+    /// ```
+    /// x . .
+    /// . . .
+    /// . . .
+    /// ```
+    #[test]
+    fn shared_node_skips_illegal_moves() {
+        let node = SearchTree::new(&Goban::new(3, 3), Color::White, 0);
+        let mut goban = Goban::new(3, 3);
+        goban.play((0u8, 0u8).into(), Color::Black);
+
+        let mut rng = Random::new(0);
+        for _ in 0..200 {
+            let result = node.probe(goban.clone(), 0.5, &mut rng);
+
+            assert!((0.0..=1.0).contains(&result.value(Color::White)));
+        }
+
+        assert_eq!(node.analysis().get(Some((0u8, 0u8).into())).map(|mv| mv.visits()), Some(0));
+    }
+}
@@ -0,0 +1,259 @@
+mod parser;
+mod reader;
+mod writer;
+
+use crate::{Color, Point, record::{GameRecord, Move}};
+use std::fmt::Display;
+
+pub use self::parser::*;
+pub use self::reader::*;
+
+/// The error returned when an SGF file could not be parsed, or does not
+/// describe a valid game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SgfError {
+    /// The text does not contain any game trees.
+    Empty,
+
+    /// The text ended in the middle of a game tree.
+    UnexpectedEnd,
+
+    /// The given character was found at the given byte offset, where it was
+    /// not expected.
+    UnexpectedChar(char, usize),
+
+    /// The property with the given name has a value that could not be
+    /// understood.
+    InvalidValue(String, String),
+
+    /// The property with the given name is not supported.
+    Unsupported(String),
+}
+
+impl Display for SgfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no game trees found"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of file"),
+            Self::UnexpectedChar(ch, offset) => write!(f, "unexpected character {:?} at offset {}", ch, offset),
+            Self::InvalidValue(name, value) => write!(f, "invalid value {}[{}]", name, value),
+            Self::Unsupported(name) => write!(f, "unsupported property {}", name),
+        }
+    }
+}
+
+impl std::error::Error for SgfError {
+    // pass
+}
+
+/// The largest board size supported, as SGF only has coordinates for 26
+/// lines and the `Goban` is limited further than that.
+const MAX_SIZE: usize = 25;
+
+fn invalid_value(name: &str, value: &str) -> SgfError {
+    SgfError::InvalidValue(name.into(), value.into())
+}
+
+fn parse_size(value: &str) -> Result<(usize, usize), SgfError> {
+    let (width, height) = value.split_once(':').unwrap_or((value, value));
+    let width = width.trim().parse::<usize>().map_err(|_| invalid_value("SZ", value))?;
+    let height = height.trim().parse::<usize>().map_err(|_| invalid_value("SZ", value))?;
+
+    if width == 0 || height == 0 || width > MAX_SIZE || height > MAX_SIZE {
+        Err(invalid_value("SZ", value))
+    } else {
+        Ok((width, height))
+    }
+}
+
+fn parse_coordinate(ch: u8) -> Option<u8> {
+    if ch.is_ascii_lowercase() {
+        Some(ch - b'a')
+    } else {
+        None
+    }
+}
+
+fn parse_point(name: &str, value: &str, (width, height): (usize, usize)) -> Result<Point, SgfError> {
+    match value.as_bytes() {
+        &[x, y] => {
+            let x = parse_coordinate(x).filter(|&x| (x as usize) < width);
+            let y = parse_coordinate(y).filter(|&y| (y as usize) < height);
+
+            x.zip(y).map(Point::from).ok_or_else(|| invalid_value(name, value))
+        },
+        _ => Err(invalid_value(name, value))
+    }
+}
+
+fn parse_move(name: &str, value: &str, (width, height): (usize, usize)) -> Result<Option<Point>, SgfError> {
+    if value.is_empty() || (value == "tt" && width <= 19 && height <= 19) {
+        Ok(None)
+    } else {
+        parse_point(name, value, (width, height)).map(Some)
+    }
+}
+
+/// Returns all points in the given point list `value`, which is either a
+/// single point, or a compressed rectangle such as `aa:cc`.
+fn parse_point_list(name: &str, value: &str, size: (usize, usize)) -> Result<Vec<Point>, SgfError> {
+    if let Some((from, to)) = value.split_once(':') {
+        let (x0, y0): (u8, u8) = parse_point(name, from, size)?.into();
+        let (x1, y1): (u8, u8) = parse_point(name, to, size)?.into();
+
+        Ok((y0.min(y1)..=y0.max(y1))
+            .flat_map(|y| (x0.min(x1)..=x0.max(x1)).map(move |x| Point::from((x, y))))
+            .collect())
+    } else {
+        Ok(vec! [parse_point(name, value, size)?])
+    }
+}
+
+impl GameRecord {
+    /// Returns the record of the main line of the given SGF game tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `tree` -
+    ///
+    pub fn from_sgf(tree: &GameTree) -> Result<Self, SgfError> {
+        Self::from_sgf_nodes(&tree.main_line())
+    }
+
+    /// Returns the record of the given sequence of SGF nodes `nodes`, where
+    /// the first node is the root node of the game. See `GameTree::path` for
+    /// how to pick a sequence other than the main line.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` -
+    ///
+    pub fn from_sgf_nodes(nodes: &[&Node]) -> Result<Self, SgfError> {
+        let root = nodes.first().ok_or(SgfError::Empty)?;
+        let size = root.get("SZ").map(|prop| parse_size(prop.value())).unwrap_or(Ok((19, 19)))?;
+        let mut record = GameRecord::new(size.0, size.1);
+
+        for prop in root.properties() {
+            let value = prop.value().trim();
+
+            match prop.name() {
+                "GM" if value != "1" => { return Err(invalid_value("GM", value)) },
+                "KM" => { record.set_komi(value.parse().map_err(|_| invalid_value("KM", value))?) },
+                "HA" => { record.set_handicap(value.parse().map_err(|_| invalid_value("HA", value))?) },
+                "PB" => { record.set_black_name(value) },
+                "PW" => { record.set_white_name(value) },
+                "RE" => { record.set_result(value) },
+                "RU" => { record.set_rules(value) },
+                _ => {}
+            }
+        }
+
+        for node in nodes {
+            for prop in node.properties() {
+                let color = match prop.name() {
+                    "AB" => Some(Color::Black),
+                    "AW" => Some(Color::White),
+                    "AE" => None,
+                    _ => { continue },
+                };
+
+                for value in prop.values() {
+                    for at in parse_point_list(prop.name(), value, size)? {
+                        match color {
+                            Some(color) => record.push_setup(color, at),
+                            None => record.push_clear(at),
+                        }
+                    }
+                }
+            }
+
+            for prop in node.properties() {
+                let color = match prop.name() {
+                    "B" => Color::Black,
+                    "W" => Color::White,
+                    _ => { continue },
+                };
+
+                record.push_move(match parse_move(prop.name(), prop.value(), size)? {
+                    Some(at) => Move::play(color, at),
+                    None => Move::pass(color),
+                });
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Illegal, record::{Action, ReplayError}};
+
+    #[test]
+    fn from_sgf_reads_game_info() {
+        let trees = parse("(;GM[1]SZ[13]KM[6.5]HA[2]PB[Black]PW[White]RE[W+R]AB[dd][jj];W[dj];B[])").unwrap();
+        let record = GameRecord::from_sgf(&trees[0]).unwrap();
+
+        assert_eq!((record.width(), record.height()), (13, 13));
+        assert_eq!(record.komi(), 6.5);
+        assert_eq!(record.handicap(), 2);
+        assert_eq!(record.black_name(), Some("Black"));
+        assert_eq!(record.white_name(), Some("White"));
+        assert_eq!(record.result(), Some("W+R"));
+        assert_eq!(record.actions().len(), 4);
+        assert_eq!(record.moves().collect::<Vec<_>>(), vec! [
+            Move::play(Color::White, (3u8, 9u8).into()),
+            Move::pass(Color::Black),
+        ]);
+    }
+
+    #[test]
+    fn from_sgf_expands_point_lists() {
+        let trees = parse("(;SZ[9]AB[aa:bc])").unwrap();
+        let record = GameRecord::from_sgf(&trees[0]).unwrap();
+
+        assert_eq!(record.actions().len(), 6);
+    }
+
+    #[test]
+    fn from_sgf_reads_cleared_points() {
+        let trees = parse("(;SZ[9]AB[aa][bb];AE[aa];W[aa])").unwrap();
+        let record = GameRecord::from_sgf(&trees[0]).unwrap();
+
+        assert_eq!(record.actions()[2], Action::Clear((0u8, 0u8).into()));
+
+        let goban = record.replay().play_to_end().unwrap();
+
+        assert_eq!(goban.at((0u8, 0u8).into()), Some(Color::White));
+        assert_eq!(goban.at((1u8, 1u8).into()), Some(Color::Black));
+    }
+
+    #[test]
+    fn from_sgf_rejects_points_outside_board() {
+        let trees = parse("(;SZ[9];B[jj])").unwrap();
+
+        assert_eq!(GameRecord::from_sgf(&trees[0]), Err(SgfError::InvalidValue("B".into(), "jj".into())));
+    }
+
+    #[test]
+    fn replay_reports_move_number_of_illegal_move() {
+        let trees = parse("(;SZ[9];B[ba];W[ca];B[ab];W[aa])").unwrap();
+        let record = GameRecord::from_sgf(&trees[0]).unwrap();
+
+        assert_eq!(
+            record.replay().play_to_end().err(),
+            Some(ReplayError::IllegalMove(4, Move::play(Color::White, (0u8, 0u8).into()), Illegal::Suicide))
+        );
+    }
+
+    #[test]
+    fn replay_follows_variation() {
+        let trees = parse("(;SZ[9];B[ee](;W[cc])(;W[gg];B[cc]))").unwrap();
+        let record = GameRecord::from_sgf_nodes(&trees[0].path(&[1]).unwrap()).unwrap();
+        let goban = record.replay().play_to_end().unwrap();
+
+        assert_eq!(goban.at((6u8, 6u8).into()), Some(Color::White));
+        assert_eq!(goban.at((2u8, 2u8).into()), Some(Color::Black));
+    }
+}
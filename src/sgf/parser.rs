@@ -0,0 +1,325 @@
+use super::SgfError;
+use std::{iter::Peekable, str::CharIndices};
+
+/// A single property of a node, e.g. `B[pd]` or `AB[dd][pp]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Property {
+    name: String,
+    values: Vec<String>,
+}
+
+impl Property {
+    pub fn new(name: String, values: Vec<String>) -> Self {
+        Self { name, values }
+    }
+
+    /// Returns the identifier of this property.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns all values of this property, with escape sequences removed.
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    /// Returns the first value of this property.
+    pub fn value(&self) -> &str {
+        self.values.first().map(|v| v.as_str()).unwrap_or("")
+    }
+}
+
+/// A single node in a game tree, i.e. everything that follows a `;`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Node {
+    properties: Vec<Property>,
+}
+
+impl Node {
+    pub fn new(properties: Vec<Property>) -> Self {
+        Self { properties }
+    }
+
+    /// Returns all properties of this node in the order they were given.
+    pub fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    /// Returns the property with the given identifier `name`, if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` -
+    ///
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|prop| prop.name == name)
+    }
+}
+
+/// A sequence of nodes, followed by zero or more variations. The first
+/// variation is by convention the main line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameTree {
+    nodes: Vec<Node>,
+    variations: Vec<GameTree>,
+}
+
+impl GameTree {
+    pub fn new(nodes: Vec<Node>, variations: Vec<GameTree>) -> Self {
+        Self { nodes, variations }
+    }
+
+    /// Returns the sequence of nodes at the start of this tree.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Returns the variations that follow the sequence of nodes.
+    pub fn variations(&self) -> &[GameTree] {
+        &self.variations
+    }
+
+    /// Returns the root node of this tree.
+    pub fn root(&self) -> Option<&Node> {
+        self.nodes.first()
+    }
+
+    /// Returns all nodes along the main line of this tree, i.e. the path that
+    /// always picks the first variation.
+    pub fn main_line(&self) -> Vec<&Node> {
+        let mut nodes = vec! [];
+        let mut tree = self;
+
+        loop {
+            nodes.extend(tree.nodes.iter());
+
+            if let Some(next_tree) = tree.variations.first() {
+                tree = next_tree;
+            } else {
+                break nodes;
+            }
+        }
+    }
+
+    /// Returns all nodes along the given path of this tree, where the `i`th
+    /// element of `path` is the variation to pick at the `i`th branching
+    /// point. Once `path` is exhausted the first variation is picked. Returns
+    /// `None` if the path refers to a variation that does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` -
+    ///
+    pub fn path(&self, path: &[usize]) -> Option<Vec<&Node>> {
+        let mut nodes = vec! [];
+        let mut path = path.iter();
+        let mut tree = self;
+
+        loop {
+            nodes.extend(tree.nodes.iter());
+
+            if tree.variations.is_empty() {
+                break if path.next().is_none() { Some(nodes) } else { None };
+            }
+
+            tree = tree.variations.get(*path.next().unwrap_or(&0))?;
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            chars: text.char_indices().peekable(),
+        }
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map(|&(i, _)| i).unwrap_or(self.text.len())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {
+            // pass
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SgfError> {
+        self.skip_whitespace();
+
+        match self.chars.next() {
+            Some((_, ch)) if ch == expected => Ok(()),
+            Some((offset, ch)) => Err(SgfError::UnexpectedChar(ch, offset)),
+            None => Err(SgfError::UnexpectedEnd),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|&(_, ch)| ch)
+    }
+
+    fn parse_collection(&mut self) -> Result<Vec<GameTree>, SgfError> {
+        let mut trees = vec! [];
+
+        // anything before the first game tree is ignored, as many files
+        // contain a header or a byte order mark
+        while let Some((_, ch)) = self.chars.peek() {
+            if *ch == '(' {
+                trees.push(self.parse_game_tree()?);
+            } else {
+                self.chars.next();
+            }
+        }
+
+        if trees.is_empty() {
+            Err(SgfError::Empty)
+        } else {
+            Ok(trees)
+        }
+    }
+
+    fn parse_game_tree(&mut self) -> Result<GameTree, SgfError> {
+        self.expect('(')?;
+
+        let mut nodes = vec! [];
+        while self.peek() == Some(';') {
+            nodes.push(self.parse_node()?);
+        }
+
+        if nodes.is_empty() {
+            let offset = self.offset();
+
+            return match self.chars.next() {
+                Some((_, ch)) => Err(SgfError::UnexpectedChar(ch, offset)),
+                None => Err(SgfError::UnexpectedEnd),
+            };
+        }
+
+        let mut variations = vec! [];
+        while self.peek() == Some('(') {
+            variations.push(self.parse_game_tree()?);
+        }
+
+        self.expect(')')?;
+
+        Ok(GameTree { nodes, variations })
+    }
+
+    fn parse_node(&mut self) -> Result<Node, SgfError> {
+        self.expect(';')?;
+
+        let mut properties = vec! [];
+        while self.peek().map(|ch| ch.is_ascii_alphabetic()).unwrap_or(false) {
+            properties.push(self.parse_property()?);
+        }
+
+        Ok(Node { properties })
+    }
+
+    fn parse_property(&mut self) -> Result<Property, SgfError> {
+        let mut name = String::new();
+
+        // lower case letters are allowed in property identifiers by older
+        // versions of the standard, but they carry no meaning
+        while let Some((_, ch)) = self.chars.next_if(|(_, ch)| ch.is_ascii_alphabetic()) {
+            if ch.is_ascii_uppercase() {
+                name.push(ch);
+            }
+        }
+
+        let mut values = vec! [];
+        while self.peek() == Some('[') {
+            values.push(self.parse_value()?);
+        }
+
+        if values.is_empty() {
+            let offset = self.offset();
+
+            match self.chars.next() {
+                Some((_, ch)) => Err(SgfError::UnexpectedChar(ch, offset)),
+                None => Err(SgfError::UnexpectedEnd),
+            }
+        } else {
+            Ok(Property { name, values })
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<String, SgfError> {
+        self.expect('[')?;
+
+        let mut value = String::new();
+
+        loop {
+            match self.chars.next() {
+                None => { return Err(SgfError::UnexpectedEnd) },
+                Some((_, ']')) => { return Ok(value) },
+                Some((_, '\\')) => {
+                    match self.chars.next() {
+                        None => { return Err(SgfError::UnexpectedEnd) },
+                        Some((_, '\n')) => { self.chars.next_if(|&(_, ch)| ch == '\r'); },
+                        Some((_, '\r')) => { self.chars.next_if(|&(_, ch)| ch == '\n'); },
+                        Some((_, ch)) => { value.push(ch) },
+                    }
+                },
+                Some((_, ch)) => { value.push(ch) },
+            }
+        }
+    }
+}
+
+/// Returns all game trees in the given SGF collection `text`.
+///
+/// # Arguments
+///
+/// * `text` -
+///
+pub fn parse(text: &str) -> Result<Vec<GameTree>, SgfError> {
+    Parser::new(text).parse_collection()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_returns_main_line() {
+        let trees = parse("(;FF[4]SZ[9];B[ee];W[cc](;B[gg])(;B[cg];W[gc]))").unwrap();
+
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].main_line().len(), 4);
+        assert_eq!(trees[0].main_line()[3].get("B").unwrap().value(), "gg");
+        assert_eq!(trees[0].path(&[1]).unwrap().len(), 5);
+        assert_eq!(trees[0].path(&[2]), None);
+    }
+
+    #[test]
+    fn parse_unescapes_values() {
+        let trees = parse("(;C[a \\] b\\\nc]AB[aa][bb])").unwrap();
+        let root = trees[0].root().unwrap();
+
+        assert_eq!(root.get("C").unwrap().value(), "a ] bc");
+        assert_eq!(root.get("AB").unwrap().values(), ["aa", "bb"]);
+    }
+
+    #[test]
+    fn parse_reads_collections() {
+        let trees = parse("(;GM[1];B[aa])\n(;GM[1];B[bb])").unwrap();
+
+        assert_eq!(trees.len(), 2);
+    }
+
+    #[test]
+    fn parse_rejects_truncated_input() {
+        assert_eq!(parse("(;GM[1];B[aa]"), Err(SgfError::UnexpectedEnd));
+        assert_eq!(parse("(;GM[1];B[aa)"), Err(SgfError::UnexpectedEnd));
+        assert_eq!(parse("()"), Err(SgfError::UnexpectedChar(')', 1)));
+        assert_eq!(parse(""), Err(SgfError::Empty));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::record::GameRecord;
+use super::{parse, SgfError};
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufReader, Bytes, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc::{self, Receiver}},
+    thread,
+};
+
+/// The error returned when a game could not be read from a stream or a file.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading the file at the given path failed.
+    Io(Option<PathBuf>, io::Error),
+
+    /// The game in the file at the given path is not valid SGF.
+    Sgf(Option<PathBuf>, SgfError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "{}: ", path.display())?;
+        }
+
+        match self {
+            Self::Io(_, err) => write!(f, "{}", err),
+            Self::Sgf(_, err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    // pass
+}
+
+impl ReadError {
+    /// Returns the path of the file that could not be read, if the game was
+    /// read from a file.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Io(path, _) => path.as_deref(),
+            Self::Sgf(path, _) => path.as_deref(),
+        }
+    }
+
+    fn with_path(self, path: &Path) -> Self {
+        match self {
+            Self::Io(_, err) => Self::Io(Some(path.into()), err),
+            Self::Sgf(_, err) => Self::Sgf(Some(path.into()), err),
+        }
+    }
+}
+
+/// Reads games one at a time from a stream containing zero or more
+/// concatenated SGF collections. Only a single game is kept in memory at a
+/// time, and a broken game does not prevent the following games from being
+/// read.
+pub struct SgfReader<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    buf: Vec<u8>,
+    is_done: bool,
+}
+
+impl<R: Read> Iterator for SgfReader<R> {
+    type Item = Result<GameRecord, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        match self.next_game_tree() {
+            Ok(false) => {
+                self.is_done = true;
+                None
+            },
+            Ok(true) => {
+                let text = String::from_utf8_lossy(&self.buf);
+                let record = parse(&text).and_then(|trees| GameRecord::from_sgf(&trees[0]));
+
+                Some(record.map_err(|err| ReadError::Sgf(None, err)))
+            },
+            Err(err) => {
+                self.is_done = true;
+                Some(Err(ReadError::Io(None, err)))
+            }
+        }
+    }
+}
+
+impl<R: Read> SgfReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes(),
+            buf: vec! [],
+            is_done: false,
+        }
+    }
+
+    /// Read the next top-level game tree into `buf`, and returns if one was
+    /// found. Only the brackets and escape sequences are tracked here, the
+    /// contents of the game tree is validated by the parser.
+    fn next_game_tree(&mut self) -> io::Result<bool> {
+        let mut depth = 0;
+        let mut is_value = false;
+        let mut is_escape = false;
+
+        self.buf.clear();
+
+        for byte in self.bytes.by_ref() {
+            let byte = byte?;
+
+            if depth == 0 && byte != b'(' {
+                continue;
+            }
+
+            self.buf.push(byte);
+
+            if is_escape {
+                is_escape = false;
+            } else if is_value {
+                match byte {
+                    b'\\' => { is_escape = true },
+                    b']' => { is_value = false },
+                    _ => {}
+                }
+            } else {
+                match byte {
+                    b'[' => { is_value = true },
+                    b'(' => { depth += 1 },
+                    b')' => {
+                        depth -= 1;
+
+                        if depth == 0 {
+                            return Ok(true);
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        // a truncated game tree is still handed to the parser, so that it is
+        // reported as broken instead of silently dropped
+        Ok(!self.buf.is_empty())
+    }
+}
+
+/// Returns if the given path `path` looks like an SGF file.
+fn is_sgf(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("sgf"))
+        .unwrap_or(false)
+}
+
+/// Walks a directory tree in sorted order and returns the path of every SGF
+/// file in it. Only the listing of the directories along the current path is
+/// kept in memory.
+struct Walk {
+    stack: Vec<Vec<PathBuf>>,
+}
+
+impl Iterator for Walk {
+    type Item = Result<PathBuf, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let path = match self.stack.last_mut()?.pop() {
+                Some(path) => path,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            if path.is_dir() {
+                match Self::read_dir(&path) {
+                    Ok(entries) => self.stack.push(entries),
+                    Err(err) => return Some(Err(ReadError::Io(Some(path), err))),
+                }
+            } else if is_sgf(&path) {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+impl Walk {
+    fn new(root: &Path) -> Self {
+        Self { stack: vec! [vec! [root.into()]] }
+    }
+
+    fn read_dir(path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        // sorted in reverse, since entries are popped from the back
+        entries.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(entries)
+    }
+}
+
+/// Returns all games in the SGF file at the given path `path`.
+fn read_file(path: &Path) -> impl Iterator<Item=Result<GameRecord, ReadError>> {
+    let path = path.to_path_buf();
+    let (reader, err) = match File::open(&path) {
+        Ok(file) => (Some(SgfReader::new(file)), None),
+        Err(err) => (None, Some(ReadError::Io(None, err))),
+    };
+
+    reader.into_iter()
+        .flatten()
+        .chain(err.map(Err))
+        .map(move |result| result.map_err(|err| err.with_path(&path)))
+}
+
+/// Reads every game in all SGF files (`*.sgf`) of a directory tree, including
+/// files that contain multiple games.
+pub struct Corpus {
+    root: PathBuf,
+    skip_errors: bool,
+    num_threads: usize,
+}
+
+impl IntoIterator for Corpus {
+    type Item = Result<GameRecord, ReadError>;
+    type IntoIter = Box<dyn Iterator<Item=Self::Item>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let skip_errors = self.skip_errors;
+        let records: Self::IntoIter = if self.num_threads <= 1 {
+            Box::new(Walk::new(&self.root).flat_map(|path| -> Self::IntoIter {
+                match path {
+                    Ok(path) => Box::new(read_file(&path)),
+                    Err(err) => Box::new(Some(Err(err)).into_iter()),
+                }
+            }))
+        } else {
+            Box::new(Self::spawn(&self.root, self.num_threads))
+        };
+
+        Box::new(records.filter(move |result| !skip_errors || result.is_ok()))
+    }
+}
+
+impl Corpus {
+    /// Returns a corpus of all SGF files in the directory tree at the given
+    /// path `root`. The path may also point to a single file.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` -
+    ///
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().into(),
+            skip_errors: false,
+            num_threads: 1,
+        }
+    }
+
+    /// Sets whether broken files and games should be silently skipped, instead
+    /// of being returned as errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip_errors` -
+    ///
+    pub fn skip_errors(mut self, skip_errors: bool) -> Self {
+        self.skip_errors = skip_errors;
+        self
+    }
+
+    /// Sets the number of threads used to parse files. When more than one
+    /// thread is used the games are no longer returned in a deterministic
+    /// order.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_threads` -
+    ///
+    pub fn threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    /// Walk the directory tree on one thread, and parse the files it finds on
+    /// `num_threads` worker threads. All channels are bounded so that memory
+    /// usage does not depend on how far the workers are ahead of the consumer.
+    /// The threads exit once the returned iterator is dropped.
+    fn spawn(root: &Path, num_threads: usize) -> impl Iterator<Item=Result<GameRecord, ReadError>> {
+        let (path_tx, path_rx) = mpsc::sync_channel::<Result<PathBuf, ReadError>>(4 * num_threads);
+        let (record_tx, record_rx) = mpsc::sync_channel(4 * num_threads);
+        let path_rx = Arc::new(Mutex::new(path_rx));
+        let walk = Walk::new(root);
+
+        thread::spawn(move || {
+            for path in walk {
+                if path_tx.send(path).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..num_threads {
+            let path_rx = path_rx.clone();
+            let record_tx = record_tx.clone();
+
+            thread::spawn(move || {
+                loop {
+                    let records: Box<dyn Iterator<Item=_>> = match path_rx.lock().map(|rx| rx.recv()) {
+                        Ok(Ok(Ok(path))) => Box::new(read_file(&path)),
+                        Ok(Ok(Err(err))) => Box::new(Some(Err(err)).into_iter()),
+                        _ => break,
+                    };
+
+                    for record in records {
+                        if record_tx.send(record).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+
+        Receiver::into_iter(record_rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, io::Cursor};
+
+    #[test]
+    fn reader_yields_every_game() {
+        let text = "(;GM[1]SZ[9];B[ee])\n(;GM[1]SZ[9]C[a \\) b];B[cc](;W[gg])(;W[gc]))";
+        let records = SgfReader::new(Cursor::new(text)).collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].as_ref().unwrap().moves().count(), 1);
+        assert_eq!(records[1].as_ref().unwrap().moves().count(), 2);
+    }
+
+    #[test]
+    fn reader_continues_after_broken_game() {
+        let text = "(;GM[1]SZ[9];B[zz])(;GM[1]SZ[9];B[ee])(;GM[1]SZ[9];B[ee]";
+        let records = SgfReader::new(Cursor::new(text)).collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 3);
+        assert!(matches!(records[0], Err(ReadError::Sgf(None, SgfError::InvalidValue(_, _)))));
+        assert!(records[1].is_ok());
+        assert!(matches!(records[2], Err(ReadError::Sgf(None, SgfError::UnexpectedEnd))));
+    }
+
+    fn corpus_dir(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("gobit-corpus-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        fs::write(root.join("1.sgf"), "(;GM[1]SZ[9];B[aa])").unwrap();
+        fs::write(root.join("a/2.sgf"), "(;GM[1]SZ[9];B[bb])(;GM[1]SZ[9];B[cc])").unwrap();
+        fs::write(root.join("a/b/3.SGF"), "(;GM[1]SZ[9];B[dd]").unwrap();
+        fs::write(root.join("a/b/4.txt"), "(;GM[1]SZ[9];B[ee])").unwrap();
+        root
+    }
+
+    #[test]
+    fn corpus_walks_directory_tree() {
+        let root = corpus_dir("walk");
+        let records = Corpus::new(&root).into_iter().collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records.iter().filter(|r| r.is_ok()).count(), 3);
+        assert_eq!(records[3].as_ref().err().and_then(|err| err.path()), Some(root.join("a/b/3.SGF").as_path()));
+        assert_eq!(Corpus::new(&root).skip_errors(true).into_iter().count(), 3);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn corpus_reads_in_parallel() {
+        let root = corpus_dir("parallel");
+
+        assert_eq!(Corpus::new(&root).threads(3).into_iter().count(), 4);
+        assert_eq!(Corpus::new(&root).threads(3).skip_errors(true).into_iter().count(), 3);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{Color, Point, record::{Action, GameRecord}};
+use std::fmt::Write;
+
+/// Returns the given text `value` with all characters that have a special
+/// meaning inside of a property value escaped.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+fn point_to_sgf(at: Point) -> String {
+    let (x, y): (u8, u8) = at.into();
+
+    format!("{}{}", (b'a' + x) as char, (b'a' + y) as char)
+}
+
+fn color_to_sgf(color: Color) -> char {
+    match color {
+        Color::Black => 'B',
+        Color::White => 'W',
+    }
+}
+
+/// Write a single node containing all of the given setup stones `setup`,
+/// where a color of `None` is a cleared point.
+fn write_setup(sgf: &mut String, setup: &[(Option<Color>, Point)]) {
+    for color in [None, Some(Color::Black), Some(Color::White)] {
+        let points = setup.iter()
+            .filter(|(other, _)| *other == color)
+            .map(|(_, at)| format!("[{}]", point_to_sgf(*at)))
+            .collect::<String>();
+
+        if !points.is_empty() {
+            let _ = write!(sgf, "A{}{}", color.map(color_to_sgf).unwrap_or('E'), points);
+        }
+    }
+}
+
+impl GameRecord {
+    /// Returns this game as an SGF (FF[4]) game tree.
+    pub fn to_sgf(&self) -> String {
+        let mut sgf = String::from("(;GM[1]FF[4]CA[UTF-8]");
+
+        if self.width() == self.height() {
+            let _ = write!(sgf, "SZ[{}]", self.width());
+        } else {
+            let _ = write!(sgf, "SZ[{}:{}]", self.width(), self.height());
+        }
+
+        let _ = write!(sgf, "KM[{}]", self.komi());
+        if self.handicap() > 0 {
+            let _ = write!(sgf, "HA[{}]", self.handicap());
+        }
+
+        for (name, value) in [("PB", self.black_name()), ("PW", self.white_name()), ("RE", self.result()), ("RU", self.rules())] {
+            if let Some(value) = value {
+                let _ = write!(sgf, "{}[{}]", name, escape(value));
+            }
+        }
+
+        // consecutive setup stones are collected into a single node, which
+        // for the stones before the first move is the root node
+        let mut setup = vec! [];
+        let mut is_root = true;
+
+        for action in self.actions() {
+            match *action {
+                Action::Setup(color, at) => { setup.push((Some(color), at)) },
+                Action::Clear(at) => { setup.push((None, at)) },
+                Action::Move(mv) => {
+                    if !setup.is_empty() {
+                        if !is_root {
+                            sgf.push(';');
+                        }
+
+                        write_setup(&mut sgf, &setup);
+                        setup.clear();
+                    }
+
+                    is_root = false;
+                    let _ = write!(
+                        sgf,
+                        ";{}[{}]",
+                        color_to_sgf(mv.color()),
+                        mv.at().map(point_to_sgf).unwrap_or_default()
+                    );
+                }
+            }
+        }
+
+        if !setup.is_empty() {
+            if !is_root {
+                sgf.push(';');
+            }
+
+            write_setup(&mut sgf, &setup);
+        }
+
+        sgf.push(')');
+        sgf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{record::Move, sgf::parse};
+
+    #[test]
+    fn to_sgf_round_trips() {
+        let mut record = GameRecord::new(9, 9);
+        record.set_komi(6.5);
+        record.set_handicap(2);
+        record.set_black_name("Black]");
+        record.set_result("W+R");
+        record.push_setup(Color::Black, (2u8, 6u8).into());
+        record.push_setup(Color::Black, (6u8, 2u8).into());
+        record.push_move(Move::play(Color::White, (4u8, 4u8).into()));
+        record.push_setup(Color::White, (0u8, 0u8).into());
+        record.push_move(Move::pass(Color::Black));
+
+        let sgf = record.to_sgf();
+
+        assert_eq!(sgf, "(;GM[1]FF[4]CA[UTF-8]SZ[9]KM[6.5]HA[2]PB[Black\\]]RE[W+R]AB[cg][gc];W[ee];AW[aa];B[])");
+        assert_eq!(GameRecord::from_sgf(&parse(&sgf).unwrap()[0]), Ok(record));
+    }
+
+    #[test]
+    fn to_sgf_writes_cleared_points() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_setup(Color::Black, (2u8, 6u8).into());
+        record.push_move(Move::play(Color::White, (4u8, 4u8).into()));
+        record.push_clear((2u8, 6u8).into());
+        record.push_setup(Color::White, (0u8, 0u8).into());
+
+        let sgf = record.to_sgf();
+
+        assert_eq!(sgf, "(;GM[1]FF[4]CA[UTF-8]SZ[9]KM[0]AB[cg];W[ee];AE[cg]AW[aa])");
+        assert_eq!(GameRecord::from_sgf(&parse(&sgf).unwrap()[0]), Ok(record));
+    }
+
+    #[test]
+    fn to_sgf_exports_gib() {
+        let record = GameRecord::from_gib("\\[GAMEBLACKNAME=Black\\]\nINI 0 1 0 &4\nSTO 0 2 1 3 15\n").unwrap();
+
+        assert_eq!(record.to_sgf(), "(;GM[1]FF[4]CA[UTF-8]SZ[19]KM[0]PB[Black];B[dp])");
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{Color, Goban, Point, record::{fixed_handicap, GameRecord}};
+use std::{fmt::{Display, Write}, ops::RangeInclusive};
+
+/// A mark drawn on top of a point of the board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Markup {
+    Circle,
+    Square,
+    Triangle,
+    Cross,
+    Label(String),
+}
+
+/// Renders a `Goban` position as a Scalable Vector Graphics (SVG) diagram,
+/// with optional coordinates, move numbers, markup and a heatmap overlay.
+pub struct Svg<'a> {
+    goban: &'a Goban,
+    cell_size: f32,
+    coordinates: bool,
+    labels: Vec<(Point, Markup)>,
+    heatmap: Option<Vec<f32>>,
+}
+
+impl<'a> Svg<'a> {
+    /// The letters used for the columns, following the convention of skipping
+    /// the letter `I`.
+    const COLUMNS: &'static [u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+    /// Returns a diagram of the given position `goban`, without coordinates
+    /// or any markup.
+    ///
+    /// # Arguments
+    ///
+    /// * `goban` -
+    ///
+    pub fn new(goban: &'a Goban) -> Self {
+        Self {
+            goban,
+            cell_size: 24.0,
+            coordinates: false,
+            labels: vec! [],
+            heatmap: None,
+        }
+    }
+
+    /// Sets the distance between two lines, in pixels.
+    pub fn cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Sets whether the coordinates should be drawn along the edges.
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Draw the given markup `markup` at `at`.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` -
+    /// * `markup` -
+    ///
+    pub fn mark(mut self, at: Point, markup: Markup) -> Self {
+        self.labels.retain(|(other, _)| *other != at);
+        self.labels.push((at, markup));
+        self
+    }
+
+    /// Label every stone played during the moves in `range` of the given game
+    /// `record` with its move number, where the first move of the game is `1`.
+    /// If the same point is played more than once within `range` the most
+    /// recent move number is shown.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` -
+    /// * `range` -
+    ///
+    pub fn move_numbers(mut self, record: &GameRecord, range: RangeInclusive<usize>) -> Self {
+        for (number, mv) in record.moves().enumerate().map(|(i, mv)| (i + 1, mv)) {
+            if let (true, Some(at)) = (range.contains(&number), mv.at()) {
+                self = self.mark(at, Markup::Label(number.to_string()));
+            }
+        }
+
+        self
+    }
+
+    /// Overlay the given per-point values `heatmap`, in the order returned by
+    /// `Goban::iter()`, on the board. The values should be in the range
+    /// `[-1, 1]`, where positive values are drawn as black and negative values
+    /// as white, for example the ownership of each point. Panics if there is
+    /// not exactly one value per point.
+    ///
+    /// # Arguments
+    ///
+    /// * `heatmap` -
+    ///
+    pub fn heatmap(mut self, heatmap: &[f32]) -> Self {
+        assert_eq!(heatmap.len(), self.goban.width() * self.goban.height(), "heatmap must have one value per point");
+
+        self.heatmap = Some(heatmap.to_vec());
+        self
+    }
+
+    fn margin(&self) -> f32 {
+        if self.coordinates { 1.5 * self.cell_size } else { self.cell_size }
+    }
+
+    /// Returns the center of the given point `at` in pixels.
+    fn center_of(&self, at: Point) -> (f32, f32) {
+        let (x, y): (usize, usize) = at.into();
+
+        (self.margin() + x as f32 * self.cell_size, self.margin() + y as f32 * self.cell_size)
+    }
+
+    fn write_grid(&self, f: &mut impl Write) -> std::fmt::Result {
+        let (width, height) = (self.goban.width(), self.goban.height());
+        let (x0, y0) = self.center_of((0usize, 0usize).into());
+        let (x1, y1) = self.center_of((width - 1, height - 1).into());
+
+        writeln!(f, "<g stroke=\"#000\" stroke-width=\"1\">")?;
+        for x in 0..width {
+            let (cx, _) = self.center_of((x, 0).into());
+            writeln!(f, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", cx, y0, cx, y1)?;
+        }
+        for y in 0..height {
+            let (_, cy) = self.center_of((0, y).into());
+            writeln!(f, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", x0, cy, x1, cy)?;
+        }
+        writeln!(f, "</g>")?;
+
+        // only the larger boards have star points on the sides
+        let num_hoshi = if width.min(height) >= 19 { 9 } else { 5 };
+        let hoshi = fixed_handicap(width, height, num_hoshi)
+            .or_else(|| fixed_handicap(width, height, 4))
+            .unwrap_or_default();
+
+        for at in hoshi {
+            let (cx, cy) = self.center_of(at);
+            writeln!(f, "<circle class=\"hoshi\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#000\"/>", cx, cy, 0.12 * self.cell_size)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_coordinates(&self, f: &mut impl Write) -> std::fmt::Result {
+        let (width, height) = (self.goban.width(), self.goban.height());
+        let offset = 0.9 * self.cell_size;
+
+        writeln!(f, "<g font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">", 0.45 * self.cell_size)?;
+        for x in 0..width {
+            let (cx, cy) = self.center_of((x, 0).into());
+            let (_, cy_bottom) = self.center_of((x, height - 1).into());
+            let letter = Self::COLUMNS[x] as char;
+
+            writeln!(f, "<text x=\"{}\" y=\"{}\">{}</text>", cx, cy - offset, letter)?;
+            writeln!(f, "<text x=\"{}\" y=\"{}\">{}</text>", cx, cy_bottom + offset, letter)?;
+        }
+        for y in 0..height {
+            let (cx, cy) = self.center_of((0, y).into());
+            let (cx_right, _) = self.center_of((width - 1, y).into());
+
+            writeln!(f, "<text x=\"{}\" y=\"{}\">{}</text>", cx - offset, cy, height - y)?;
+            writeln!(f, "<text x=\"{}\" y=\"{}\">{}</text>", cx_right + offset, cy, height - y)?;
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_heatmap(&self, f: &mut impl Write, heatmap: &[f32]) -> std::fmt::Result {
+        let size = self.cell_size;
+
+        writeln!(f, "<g class=\"heatmap\">")?;
+        for (at, &value) in self.goban.iter().zip(heatmap.iter()) {
+            let value = value.clamp(-1.0, 1.0);
+
+            if value != 0.0 {
+                let (cx, cy) = self.center_of(at);
+                let fill = if value > 0.0 { "#000" } else { "#fff" };
+
+                writeln!(
+                    f,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>",
+                    cx - 0.5 * size, cy - 0.5 * size, size, size, fill, 0.6 * value.abs()
+                )?;
+            }
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_stones(&self, f: &mut impl Write) -> std::fmt::Result {
+        for at in self.goban.iter() {
+            let (cx, cy) = self.center_of(at);
+            let fill = match self.goban.at(at) {
+                None => { continue },
+                Some(Color::Black) => "#000",
+                Some(Color::White) => "#fff",
+            };
+
+            writeln!(
+                f,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"#000\" stroke-width=\"1\"/>",
+                cx, cy, 0.48 * self.cell_size, fill
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_markup(&self, f: &mut impl Write) -> std::fmt::Result {
+        let size = self.cell_size;
+
+        for (at, markup) in &self.labels {
+            let (cx, cy) = self.center_of(*at);
+            let color = match self.goban.at(*at) {
+                Some(Color::Black) => "#fff",
+                _ => "#000",
+            };
+
+            match markup {
+                Markup::Circle => {
+                    writeln!(f, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>", cx, cy, 0.25 * size, color)?;
+                },
+                Markup::Square => {
+                    let r = 0.22 * size;
+                    writeln!(f, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>", cx - r, cy - r, 2.0 * r, 2.0 * r, color)?;
+                },
+                Markup::Triangle => {
+                    let r = 0.28 * size;
+                    writeln!(
+                        f,
+                        "<polygon points=\"{},{} {},{} {},{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+                        cx, cy - r, cx - 0.866 * r, cy + 0.5 * r, cx + 0.866 * r, cy + 0.5 * r, color
+                    )?;
+                },
+                Markup::Cross => {
+                    let r = 0.2 * size;
+                    writeln!(
+                        f,
+                        "<path d=\"M{} {}L{} {}M{} {}L{} {}\" stroke=\"{}\" stroke-width=\"2\"/>",
+                        cx - r, cy - r, cx + r, cy + r, cx - r, cy + r, cx + r, cy - r, color
+                    )?;
+                },
+                Markup::Label(text) => {
+                    // hide the grid lines behind labels on empty points
+                    if self.goban.at(*at).is_none() {
+                        writeln!(f, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#dcb35c\"/>", cx, cy, 0.4 * size)?;
+                    }
+
+                    let font_size = if text.chars().count() > 2 { 0.38 * size } else { 0.5 * size };
+                    writeln!(
+                        f,
+                        "<text x=\"{}\" y=\"{}\" fill=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                        cx, cy, color, font_size, escape(text)
+                    )?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+impl<'a> Display for Svg<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let margin = self.margin();
+        let width = 2.0 * margin + (self.goban.width() - 1) as f32 * self.cell_size;
+        let height = 2.0 * margin + (self.goban.height() - 1) as f32 * self.cell_size;
+
+        writeln!(f, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">", width, height)?;
+        writeln!(f, "<rect width=\"{}\" height=\"{}\" fill=\"#dcb35c\"/>", width, height)?;
+        self.write_grid(f)?;
+        if self.coordinates {
+            self.write_coordinates(f)?;
+        }
+        if let Some(heatmap) = &self.heatmap {
+            self.write_heatmap(f, heatmap)?;
+        }
+        self.write_stones(f)?;
+        self.write_markup(f)?;
+        write!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::Move;
+
+    #[test]
+    fn svg_draws_stones_and_hoshi() {
+        let mut goban = Goban::new(9, 9);
+        goban.play((2u8, 2u8).into(), Color::Black);
+        goban.play((6u8, 6u8).into(), Color::White);
+
+        let svg = Svg::new(&goban).coordinates(true).to_string();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<line ").count(), 18);
+        assert_eq!(svg.matches("class=\"hoshi\"").count(), 5);
+        assert_eq!(svg.matches("fill=\"#000\" stroke=\"#000\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"#fff\" stroke=\"#000\"").count(), 1);
+        assert!(svg.contains(">J</text>"));
+        assert!(!svg.contains(">I</text>"));
+    }
+
+    #[test]
+    fn svg_draws_hoshi_by_board_size() {
+        for (size, num_hoshi) in [(7, 4), (9, 5), (13, 5), (19, 9)] {
+            let svg = Svg::new(&Goban::new(size, size)).to_string();
+
+            assert_eq!(svg.matches("class=\"hoshi\"").count(), num_hoshi, "{}x{}", size, size);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn svg_rejects_heatmap_of_wrong_size() {
+        let goban = Goban::new(9, 9);
+        let _ = Svg::new(&goban).heatmap(&[0.0; 80]);
+    }
+
+    #[test]
+    fn svg_draws_move_numbers_and_heatmap() {
+        let mut record = GameRecord::new(9, 9);
+        record.push_move(Move::play(Color::Black, (2u8, 2u8).into()));
+        record.push_move(Move::play(Color::White, (6u8, 6u8).into()));
+        record.push_move(Move::play(Color::Black, (2u8, 6u8).into()));
+
+        let goban = record.replay().play_to_end().unwrap();
+        let mut heatmap = vec! [0.0; 81];
+        heatmap[0] = 1.0;
+        heatmap[80] = -0.5;
+
+        let svg = Svg::new(&goban)
+            .move_numbers(&record, 2..=3)
+            .mark((4u8, 4u8).into(), Markup::Triangle)
+            .heatmap(&heatmap)
+            .to_string();
+
+        assert!(!svg.contains(">1</text>"));
+        assert!(svg.contains(">2</text>"));
+        assert!(svg.contains(">3</text>"));
+        assert_eq!(svg.matches("<polygon ").count(), 1);
+        assert_eq!(svg.matches("fill-opacity=").count(), 2);
+    }
+}